@@ -0,0 +1,64 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+use serde::Serialize;
+use serenity::all::Timestamp;
+
+/// Every gameserver control action is appended to this file, one json object per line.
+const AUDIT_LOG: &str = "./logs/audit.log";
+
+static WRITER: LazyLock<Mutex<Option<BufWriter<File>>>> = LazyLock::new(|| {
+    if let Some(parent) = Path::new(AUDIT_LOG).parent() {
+        fs::create_dir_all(parent).expect("failed to create parent dir for audit log.");
+    }
+    match OpenOptions::new().create(true).append(true).open(AUDIT_LOG) {
+        Ok(file) => Mutex::new(Some(BufWriter::new(file))),
+        Err(why) => {
+            println!("could not open audit log at {AUDIT_LOG}: {why}");
+            Mutex::new(None)
+        }
+    }
+});
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    time: Timestamp,
+    /// Who did it, usually a discord user id.
+    actor: &'a str,
+    action: &'a str,
+    server: &'a str,
+    detail: &'a str,
+}
+
+/// Records an action in the audit log.
+pub fn record(actor: &str, action: &str, server: &str, detail: &str) {
+    let entry = AuditEntry {
+        time: Timestamp::now(),
+        actor,
+        action,
+        server,
+        detail,
+    };
+    println!("audit: {actor} {action} {server} {detail}");
+
+    let mut writer = WRITER.lock().expect("audit log lock poisoned");
+    if let Some(writer) = writer.as_mut() {
+        let line = serde_json::to_string(&entry).expect("failed to serialize audit entry.");
+        // flushed right away, entries should not get lost if the bot crashes
+        if let Err(why) = writeln!(writer, "{line}").and_then(|_| writer.flush()) {
+            println!("could not write audit log: {why}");
+        }
+    }
+}
+
+/// Makes sure everything recorded so far is written, e.g. before the bot exits.
+pub fn flush() {
+    let mut writer = WRITER.lock().expect("audit log lock poisoned");
+    if let Some(writer) = writer.as_mut() {
+        if let Err(why) = writer.flush() {
+            println!("could not flush audit log: {why}");
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+};
+use serenity::prelude::*;
+
+use super::graceful::{self, Progress};
+use super::{cooldown, reply};
+use crate::permissions;
+use crate::servers::status::ServerState;
+use crate::servers::{self, dependencies, format_duration, tracker};
+
+/// Prefix of the custom id of confirm buttons, followed by "<action>:<delay>:<server>".
+pub const CONFIRM_PREFIX: &str = "confirm:";
+pub const CANCEL_ID: &str = "cancel";
+
+/// Actions which have to be confirmed, as they may kick players.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Stop,
+    Restart,
+}
+
+impl Action {
+    /// The action belonging to a subcommand, if it has to be confirmed.
+    pub fn from_name(name: &str) -> Option<Action> {
+        match name {
+            "stop" => Some(Action::Stop),
+            "restart" => Some(Action::Restart),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Stop => "stop",
+            Action::Restart => "restart",
+        }
+    }
+}
+
+/// Asks the user to confirm an action, the reply is only visible to them.
+/// `delay` is the number of seconds players are warned for before stopping.
+pub async fn ask(
+    ctx: &Context,
+    action: Action,
+    server: &str,
+    delay: u64,
+) -> CreateInteractionResponseMessage {
+    if !servers::exists(server) {
+        return reply(format!("There is no gameserver called {server}.")).ephemeral(true);
+    }
+    let tracked = tracker::probe(ctx, server).await;
+
+    let mut question = format!(
+        "Do you really want to {} **{}**?",
+        action.name(),
+        tracked.config.name
+    );
+    if delay > 0 {
+        question.push_str(&format!(
+            "\nPlayers will be warned for {} before.",
+            format_duration(Duration::from_secs(delay))
+        ));
+    }
+    let mut running_dependents: Vec<String> = vec![];
+    for dependent in dependencies::dependents(server) {
+        if tracker::probe(ctx, &dependent).await.status.state == ServerState::Running {
+            running_dependents.push(dependent);
+        }
+    }
+    if !running_dependents.is_empty() {
+        question.push_str(&format!(
+            "\n⚠️ {} depend on it and are running.",
+            running_dependents.join(", ")
+        ));
+    }
+    if tracked.status.state == ServerState::Running && tracked.status.players.is_some() {
+        question.push_str(&format!(
+            "\n{} players are online.",
+            tracked.status.players_display()
+        ));
+    }
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!(
+            "{CONFIRM_PREFIX}{}:{delay}:{server}",
+            action.name()
+        ))
+        .label(format!("Yes, {}", action.name()))
+        .style(ButtonStyle::Danger),
+        CreateButton::new(CANCEL_ID)
+            .label("Cancel")
+            .style(ButtonStyle::Secondary),
+    ]);
+
+    CreateInteractionResponseMessage::new()
+        .content(question)
+        .components(vec![buttons])
+        .ephemeral(true)
+}
+
+/// Replaces the message of a button with `content`, removing the buttons.
+pub async fn update(ctx: &Context, component: &ComponentInteraction, content: &str) {
+    let response = CreateInteractionResponse::UpdateMessage(
+        CreateInteractionResponseMessage::new()
+            .content(content)
+            .components(vec![]),
+    );
+    if let Err(why) = component.create_response(&ctx.http, response).await {
+        println!("Cannot respond to button: {why}");
+    }
+}
+
+/// Handles a click on a confirm button, `rest` is the custom id without the prefix.
+pub async fn handle_confirm(ctx: &Context, component: &ComponentInteraction, rest: &str) {
+    let mut parts = rest.splitn(3, ':');
+    let (Some(action), Some(Ok(delay)), Some(server)) = (
+        parts.next().and_then(Action::from_name),
+        parts.next().map(str::parse::<u64>),
+        parts.next(),
+    ) else {
+        println!("invalid confirm button id {}", component.data.custom_id);
+        return;
+    };
+
+    // the whitelist may have changed since the question was asked
+    if !permissions::is_whitelisted(component.user.id) {
+        update(ctx, component, "You are not on the whitelist.").await;
+        return;
+    }
+    // custom ids come from the client
+    if !servers::exists(server) {
+        update(
+            ctx,
+            component,
+            &format!("There is no gameserver called {server}."),
+        )
+        .await;
+        return;
+    }
+
+    let user = component.user.id;
+    if action == Action::Restart {
+        // the restarted server counts towards the quota of whoever restarted it
+        if let Err(why) = super::quota::check(user) {
+            update(ctx, component, &why).await;
+            return;
+        }
+    }
+    if let Err(why) = cooldown::check(ctx, Some(user), server).await {
+        update(ctx, component, &why).await;
+        return;
+    }
+
+    let working = match action {
+        Action::Stop => format!("Stopping {server}..."),
+        Action::Restart => format!("Restarting {server}..."),
+    };
+    update(ctx, component, &working).await;
+
+    let actor = user.to_string();
+    let mut progress = Progress::new(ctx, Some(component), &working);
+    let stopped = graceful::stop(ctx, server, &actor, delay, &mut progress).await;
+    if stopped.is_ok() {
+        cooldown::record(ctx, Some(user), server).await;
+    }
+
+    if action == Action::Restart && stopped.is_ok() {
+        match super::start(ctx, server, &actor, "restart").await {
+            Ok(started) | Err(started) => progress.step(started).await,
+        }
+    }
+}
+
+pub async fn handle_cancel(ctx: &Context, component: &ComponentInteraction) {
+    update(ctx, component, "Cancelled.").await;
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use serenity::all::{CommandInteraction, EditInteractionResponse};
+use serenity::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+use crate::audit;
+use crate::notifications;
+use crate::permissions;
+use crate::servers::{self, config, console, supervisor};
+
+/// How long output of a supervised server is collected after sending the line.
+const CAPTURE_TIME: Duration = Duration::from_secs(3);
+/// Leaves room for the code block in discords 2000 character limit.
+const MAX_OUTPUT: usize = 1900;
+
+/// Collects lines until CAPTURE_TIME passed.
+async fn capture(mut output: tokio::sync::broadcast::Receiver<String>) -> String {
+    let deadline = Instant::now() + CAPTURE_TIME;
+    let mut lines: Vec<String> = vec![];
+
+    loop {
+        match tokio::time::timeout_at(deadline, output.recv()).await {
+            Ok(Ok(line)) => lines.push(line),
+            Ok(Err(RecvError::Lagged(missed))) => lines.push(format!("[{missed} lines skipped]")),
+            Ok(Err(RecvError::Closed)) | Err(_) => break,
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Shortens output to fit into a message, keeping the end, and puts it into a code block.
+fn format_output(output: &str) -> String {
+    if output.trim().is_empty() {
+        return "No output.".to_string();
+    }
+
+    let output = notifications::escape_code_block(output);
+    let mut start = output.len().saturating_sub(MAX_OUTPUT);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+
+    format!("```\n{}\n```", &output[start..])
+}
+
+async fn execute(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    server: &str,
+    line: &str,
+) -> String {
+    if !servers::exists(server) {
+        return format!("There is no gameserver called {server}.");
+    }
+    let config = config::load(server);
+    if !permissions::may_use_console(interaction.user.id, &config) {
+        return format!("You may not use the console of {}.", config.name);
+    }
+
+    audit::record(&interaction.user.id.to_string(), "console", server, line);
+
+    // supervised servers answer on stdout, which is followed for a moment
+    if let Some(output) = supervisor::subscribe(ctx, server).await {
+        if let Err(why) = supervisor::write_line(ctx, server, line).await {
+            return format!("Failed to send the command: {why}");
+        }
+        return format_output(&capture(output).await);
+    }
+
+    match console::send(ctx, server, line).await {
+        Ok(response) => format_output(&response),
+        Err(why) => format!("Failed to send the command: {why}"),
+    }
+}
+
+/// Handles `/gameserver console`, responding on its own as collecting the output takes a while.
+pub async fn run(ctx: &Context, interaction: &CommandInteraction, server: &str, line: &str) {
+    // console output is only shown to whoever asked for it
+    if let Err(why) = interaction.defer_ephemeral(&ctx.http).await {
+        println!("Cannot respond to slash command: {why}");
+        return;
+    }
+
+    let content = execute(ctx, interaction, server, line).await;
+
+    if let Err(why) = interaction
+        .edit_response(&ctx.http, EditInteractionResponse::new().content(content))
+        .await
+    {
+        println!("Cannot respond to slash command: {why}");
+    }
+}
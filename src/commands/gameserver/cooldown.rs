@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serenity::all::UserId;
+use serenity::prelude::*;
+
+use crate::permissions;
+use crate::servers::{config, format_duration};
+
+/// When each user last controlled each server, and when each server was last controlled at all.
+#[derive(Default)]
+pub struct CooldownState {
+    by_user: HashMap<(UserId, String), Instant>,
+    by_server: HashMap<String, Instant>,
+}
+
+pub struct Cooldowns;
+
+impl TypeMapKey for Cooldowns {
+    type Value = Arc<Mutex<CooldownState>>;
+}
+
+fn remaining(last: Option<&Instant>, cooldown: u64) -> Option<Duration> {
+    let elapsed = last?.elapsed();
+    Duration::from_secs(cooldown).checked_sub(elapsed)
+}
+
+async fn cooldown_state(ctx: &Context) -> Arc<Mutex<CooldownState>> {
+    let data = ctx.data.read().await;
+    data.get::<Cooldowns>()
+        .expect("Cooldowns should be in the client data")
+        .clone()
+}
+
+/// Checks whether the cooldowns allow a start/stop/restart of `server`. They only start once the
+/// action succeeded, see `record`.
+/// `user` is None for actions not done by a single user (e.g. votes), only the server cooldown
+/// applies then. Admins are exempt, but still start the cooldown for everyone else.
+pub async fn check(ctx: &Context, user: Option<UserId>, server: &str) -> Result<(), String> {
+    let cooldowns = config::load(server).cooldowns;
+    let exempt = user.map(permissions::is_admin).unwrap_or(false);
+
+    let state = cooldown_state(ctx).await;
+    let state = state.lock().await;
+
+    if !exempt {
+        if let Some(user) = user {
+            let key = (user, server.to_string());
+            if let Some(left) = remaining(state.by_user.get(&key), cooldowns.user) {
+                return Err(format!(
+                    "You can control {server} again in {}.",
+                    format_duration(left)
+                ));
+            }
+        }
+        if let Some(left) = remaining(state.by_server.get(server), cooldowns.server) {
+            return Err(format!(
+                "{server} was started or stopped recently, try again in {}.",
+                format_duration(left)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Starts the cooldowns of `server` after a start/stop/restart went through.
+pub async fn record(ctx: &Context, user: Option<UserId>, server: &str) {
+    let state = cooldown_state(ctx).await;
+    let mut state = state.lock().await;
+
+    let now = Instant::now();
+    if let Some(user) = user {
+        state.by_user.insert((user, server.to_string()), now);
+    }
+    state.by_server.insert(server.to_string(), now);
+}
@@ -0,0 +1,144 @@
+use std::fs;
+
+use serenity::all::{
+    Attachment, CommandInteraction, CreateAttachment, CreateInteractionResponseMessage,
+    ResolvedOption, ResolvedValue,
+};
+
+use super::{option_str, reply};
+use crate::audit;
+use crate::permissions;
+use crate::servers::config::{self, ServerConfig};
+use crate::servers::state::now;
+use crate::servers::{self, format_file_timestamp, path, write_atomically};
+
+/// Folder inside the servers folder replaced files are backed up in.
+pub const BACKUP_DIR: &str = "backups";
+
+/// Largest file accepted by `/gameserver files put`, config files are much smaller.
+const MAX_FILE_SIZE: u32 = 8 * 1024 * 1024;
+
+fn option_attachment<'a>(options: &'a [ResolvedOption], name: &str) -> Option<&'a Attachment> {
+    options.iter().find_map(|option| match option.value {
+        ResolvedValue::Attachment(value) if option.name == name => Some(value),
+        _ => None,
+    })
+}
+
+/// Checks the user may access the files of `server`, including its settings, returning its config.
+pub fn check_access(
+    interaction: &CommandInteraction,
+    server: &str,
+) -> Result<ServerConfig, String> {
+    if !servers::exists(server) {
+        return Err(format!("There is no gameserver called {server}."));
+    }
+
+    let config = config::load(server);
+    if !permissions::may_edit_files(interaction.user.id, &config) {
+        return Err(format!("You may not access the files of {}.", config.name));
+    }
+    Ok(config)
+}
+
+/// Checks the user may access `file` of `server`, returning the display name of the server.
+fn check_file_access(
+    interaction: &CommandInteraction,
+    server: &str,
+    file: &str,
+) -> Result<String, String> {
+    let config = check_access(interaction, server)?;
+    // only exact entries of the allow list, so nothing like "../" gets through
+    if !config.files.iter().any(|allowed| allowed == file) {
+        return Err(if config.files.is_empty() {
+            format!("No files of {} may be accessed.", config.name)
+        } else {
+            format!(
+                "{file} may not be accessed. Files of {} which may: {}",
+                config.name,
+                config.files.join(", ")
+            )
+        });
+    }
+    Ok(config.name)
+}
+
+async fn get(
+    interaction: &CommandInteraction,
+    server: &str,
+    file: &str,
+) -> Result<CreateInteractionResponseMessage, String> {
+    let name = check_file_access(interaction, server, file)?;
+
+    let attachment = CreateAttachment::path(path(server, file))
+        .await
+        .map_err(|why| format!("Could not read {file}: {why}"))?;
+    audit::record(&interaction.user.id.to_string(), "files get", server, file);
+
+    Ok(reply(format!("{file} of {name}:"))
+        .add_file(attachment)
+        .ephemeral(true))
+}
+
+async fn put(
+    interaction: &CommandInteraction,
+    server: &str,
+    file: &str,
+    attachment: &Attachment,
+) -> Result<CreateInteractionResponseMessage, String> {
+    let name = check_file_access(interaction, server, file)?;
+
+    if attachment.size > MAX_FILE_SIZE {
+        return Err(format!(
+            "The file is too big, at most {} MiB are accepted.",
+            MAX_FILE_SIZE / 1024 / 1024
+        ));
+    }
+    let content = attachment
+        .download()
+        .await
+        .map_err(|why| format!("Could not download the attachment: {why}"))?;
+
+    let target = path(server, file);
+    let mut answer = format!("Replaced {file} of {name}.");
+    if fs::metadata(&target).is_ok() {
+        let backup = format!(
+            "{BACKUP_DIR}/{}.{}",
+            file.replace('/', "_"),
+            format_file_timestamp(now())
+        );
+        fs::create_dir_all(path(server, BACKUP_DIR))
+            .and_then(|_| fs::copy(&target, path(server, &backup)))
+            .map_err(|why| format!("Could not back up {file}, it was not replaced: {why}"))?;
+        answer.push_str(&format!(" The previous version was saved as {backup}."));
+    }
+
+    write_atomically(&target, content).map_err(|why| format!("Could not write {file}: {why}"))?;
+    audit::record(&interaction.user.id.to_string(), "files put", server, file);
+
+    Ok(reply(answer).ephemeral(true))
+}
+
+/// Handles `/gameserver files get|put`, `command` being the chosen subcommand.
+pub async fn run(
+    interaction: &CommandInteraction,
+    command: &ResolvedOption<'_>,
+) -> CreateInteractionResponseMessage {
+    let ResolvedValue::SubCommand(options) = &command.value else {
+        return reply("please provide a valid command");
+    };
+    let (Some(server), Some(file)) = (
+        option_str(options, "gameserver"),
+        option_str(options, "file"),
+    ) else {
+        return reply("Ok, which one tho?");
+    };
+
+    let result = match (command.name, option_attachment(options, "attachment")) {
+        ("get", _) => get(interaction, server, file).await,
+        ("put", Some(attachment)) => put(interaction, server, file, attachment).await,
+        ("put", None) => Err("Please attach the new file.".to_string()),
+        _ => Err("please provide a valid command".to_string()),
+    };
+    result.unwrap_or_else(|why| reply(why).ephemeral(true))
+}
@@ -0,0 +1,238 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serenity::all::{ComponentInteraction, EditInteractionResponse};
+use serenity::prelude::*;
+
+use crate::audit;
+use crate::servers::config::StopConfig;
+use crate::servers::status::ServerState;
+use crate::servers::{self, config, console, format_duration, state, supervisor, tracker};
+
+const VERIFY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Reports the steps of a long running action, by editing the response to a button if there is one.
+pub struct Progress {
+    ctx: Context,
+    component: Option<ComponentInteraction>,
+    lines: Vec<String>,
+}
+
+impl Progress {
+    /// `first` is the line the response already shows.
+    pub fn new(ctx: &Context, component: Option<&ComponentInteraction>, first: &str) -> Self {
+        Progress {
+            ctx: ctx.clone(),
+            component: component.cloned(),
+            lines: vec![first.to_string()],
+        }
+    }
+
+    pub async fn step(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        println!("{line}");
+        self.lines.push(line);
+
+        if let Some(component) = &self.component {
+            let content = EditInteractionResponse::new().content(self.lines.join("\n"));
+            // fails once the interaction token expired after 15 minutes, which long countdowns may hit
+            if let Err(why) = component.edit_response(&self.ctx.http, content).await {
+                println!("Cannot report progress: {why}");
+            }
+        }
+    }
+}
+
+/// Sends a console command, reporting failures only once so a missing console doesn't spam.
+async fn send(
+    ctx: &Context,
+    server: &str,
+    line: &str,
+    reported: &mut bool,
+    progress: &mut Progress,
+) {
+    if let Err(why) = console::send(ctx, server, line).await {
+        if !*reported {
+            progress
+                .step(format!("Could not send console commands: {why}"))
+                .await;
+            *reported = true;
+        }
+    }
+}
+
+/// Stops a gameserver on behalf of `actor`. During `delay` seconds players are warned in game at
+/// the configured intervals, then the save command is run before the stop script.
+pub async fn stop(
+    ctx: &Context,
+    server: &str,
+    actor: &str,
+    delay: u64,
+    progress: &mut Progress,
+) -> Result<(), String> {
+    audit::record(actor, "stop", server, &format!("delay {delay}s"));
+    let stop_config = config::load(server).stop;
+    let mut reported = false;
+
+    if delay > 0 {
+        let warn = |left: u64| {
+            stop_config
+                .warn_format
+                .replace("{time}", &format_duration(Duration::from_secs(left)))
+        };
+
+        send(ctx, server, &warn(delay), &mut reported, progress).await;
+        progress
+            .step(format!(
+                "Warned players, stopping in {}.",
+                format_duration(Duration::from_secs(delay))
+            ))
+            .await;
+
+        let mut warnings: Vec<u64> = stop_config
+            .warnings
+            .iter()
+            .copied()
+            .filter(|&w| w < delay && w > 0)
+            .collect();
+        warnings.sort_unstable_by(|a, b| b.cmp(a));
+        warnings.dedup();
+
+        let mut left = delay;
+        for warning in warnings {
+            tokio::time::sleep(Duration::from_secs(left - warning)).await;
+            left = warning;
+            send(ctx, server, &warn(left), &mut reported, progress).await;
+        }
+        tokio::time::sleep(Duration::from_secs(left)).await;
+    }
+
+    if let Some(save_command) = &stop_config.save_command {
+        send(ctx, server, save_command, &mut reported, progress).await;
+        progress.step("Saving...").await;
+        tokio::time::sleep(Duration::from_secs(stop_config.save_wait)).await;
+    }
+
+    let result = shut_down(ctx, server, &stop_config, progress).await;
+    match &result {
+        Ok(()) => {
+            state::record_stop(server);
+            progress.step("Stopped gameserver succesfully").await
+        }
+        Err(why) => {
+            progress
+                .step(format!("Failed to stop gameserver: {why}"))
+                .await
+        }
+    }
+    tracker::update(ctx, server).await;
+
+    result
+}
+
+/// Whether a server went down, as far as the bot can tell.
+#[derive(PartialEq)]
+enum Verified {
+    Down,
+    Running,
+    /// Neither a pid nor a status script to check.
+    Unknown,
+}
+
+async fn check_down(ctx: &Context, server: &str, pid: Option<u32>) -> Verified {
+    if let Some(pid) = pid {
+        return if state::pid_alive(pid) {
+            Verified::Running
+        } else {
+            Verified::Down
+        };
+    }
+    match tracker::probe(ctx, server).await.status.state {
+        ServerState::Stopped => Verified::Down,
+        ServerState::Running => Verified::Running,
+        ServerState::Unknown => Verified::Unknown,
+    }
+}
+
+/// Polls until the server is down or `timeout` seconds passed.
+async fn wait_until_down(ctx: &Context, server: &str, pid: Option<u32>, timeout: u64) -> Verified {
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    loop {
+        let verified = check_down(ctx, server, pid).await;
+        if verified != Verified::Running || Instant::now() >= deadline {
+            return verified;
+        }
+        tokio::time::sleep(VERIFY_INTERVAL).await;
+    }
+}
+
+/// Asks the server to stop, checks it actually went down and escalates to SIGTERM and SIGKILL for
+/// supervised servers that don't.
+async fn shut_down(
+    ctx: &Context,
+    server: &str,
+    stop_config: &StopConfig,
+    progress: &mut Progress,
+) -> Result<(), String> {
+    // the pid may also belong to a supervised server still running from before a bot restart
+    let pid = match supervisor::pid(ctx, server).await {
+        Some(pid) => Some(pid),
+        None => state::get(server).pid.filter(|&pid| state::pid_alive(pid)),
+    };
+    supervisor::expect_exit(ctx, server).await;
+
+    if Path::new(&servers::path(server, "stop.sh")).exists() {
+        match super::stop_gameserver(server).await {
+            Ok(()) => progress.step("Ran stop.sh.").await,
+            Err(why) => progress.step(format!("stop.sh failed: {why}")).await,
+        }
+    } else if let Some(command) = &stop_config.command {
+        match console::send(ctx, server, command).await {
+            Ok(_) => progress.step("Sent the stop command.").await,
+            Err(why) => {
+                progress
+                    .step(format!("Could not send the stop command: {why}"))
+                    .await
+            }
+        }
+    } else if pid.is_none() {
+        return Err(format!("{server} has no stop.sh"));
+    }
+
+    match wait_until_down(ctx, server, pid, stop_config.timeout).await {
+        Verified::Down => return Ok(()),
+        Verified::Unknown => {
+            progress
+                .step("Could not verify the server went down, it has no status.sh.")
+                .await;
+            return Ok(());
+        }
+        Verified::Running => {}
+    }
+
+    let timeout = format_duration(Duration::from_secs(stop_config.timeout));
+    let Some(pid) = pid else {
+        return Err(format!("{server} is still running after {timeout}"));
+    };
+
+    progress
+        .step(format!("Still running after {timeout}, sending SIGTERM."))
+        .await;
+    supervisor::signal(pid, libc::SIGTERM)?;
+    if wait_until_down(ctx, server, Some(pid), stop_config.kill_timeout).await == Verified::Down {
+        return Ok(());
+    }
+
+    progress
+        .step(format!(
+            "Still running after {}, sending SIGKILL.",
+            format_duration(Duration::from_secs(stop_config.kill_timeout))
+        ))
+        .await;
+    supervisor::signal(pid, libc::SIGKILL)?;
+    if wait_until_down(ctx, server, Some(pid), 5).await == Verified::Down {
+        Ok(())
+    } else {
+        Err(format!("{server} (pid {pid}) survived SIGKILL"))
+    }
+}
@@ -0,0 +1,80 @@
+use serenity::all::{CreateAttachment, CreateInteractionResponseMessage};
+
+use super::reply;
+use crate::servers::rotation::{self, ARCHIVE_DIR};
+use crate::servers::{self, path};
+
+/// Attachments of a single message, discord rejects more.
+const MAX_ATTACHMENTS: usize = 10;
+/// Total attachment size discord accepts without boosts.
+const MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Dates logs of a server were archived on, e.g. "2026-10-19".
+fn archive_dates(server: &str) -> Vec<String> {
+    let mut dates: Vec<String> = rotation::archives(server)
+        .iter()
+        .filter_map(|name| {
+            // e.g. "console.log.2026-10-19.120000.gz"
+            let mut parts = name.rsplit('.');
+            parts.next()?; // gz
+            parts.next()?; // time of day
+            parts.next().map(str::to_string)
+        })
+        .collect();
+    dates.dedup();
+    dates
+}
+
+/// Handles `/gameserver logs`, attaching the logs archived on `date` or listing the dates.
+pub async fn run(server: &str, date: Option<&str>) -> CreateInteractionResponseMessage {
+    if !servers::exists(server) {
+        return reply(format!("There is no gameserver called {server}."));
+    }
+
+    let Some(date) = date else {
+        let dates = archive_dates(server);
+        return reply(if dates.is_empty() {
+            format!("{server} has no archived logs.")
+        } else {
+            format!("Archived logs of {server} exist for:\n{}", dates.join("\n"))
+        });
+    };
+
+    let archived = rotation::archived_on(server, date);
+    if archived.is_empty() {
+        return reply(format!(
+            "{server} has no logs archived on {date}. Dates look like 2026-10-19."
+        ));
+    }
+
+    // the newest ones, if there are too many
+    let skipped = archived.len().saturating_sub(MAX_ATTACHMENTS);
+    let mut response = CreateInteractionResponseMessage::new();
+    let mut total_size = 0;
+    let mut too_big: Vec<String> = vec![];
+    for name in &archived[skipped..] {
+        let archive = path(server, &format!("{ARCHIVE_DIR}/{name}"));
+        match CreateAttachment::path(&archive).await {
+            Ok(attachment) if total_size + attachment.data.len() as u64 <= MAX_ATTACHMENT_SIZE => {
+                total_size += attachment.data.len() as u64;
+                response = response.add_file(attachment);
+            }
+            Ok(_) => too_big.push(name.clone()),
+            Err(why) => println!("could not read {archive}: {why}"),
+        }
+    }
+
+    let mut content = format!("Logs of {server} archived on {date}.");
+    if skipped > 0 {
+        content.push_str(&format!(
+            "\n{skipped} older ones were left out, only {MAX_ATTACHMENTS} fit into a message."
+        ));
+    }
+    if !too_big.is_empty() {
+        content.push_str(&format!(
+            "\nToo big to attach: {}. They are in the {ARCHIVE_DIR} folder of the server.",
+            too_big.join(", ")
+        ));
+    }
+    response.content(content)
+}
@@ -0,0 +1,805 @@
+pub mod confirm;
+pub mod console;
+pub mod cooldown;
+pub mod files;
+pub mod graceful;
+pub mod logs;
+pub mod queue;
+pub mod quota;
+pub mod settings;
+pub mod version;
+pub mod vote;
+
+use serenity::all::{
+    CommandInteraction, ComponentInteraction, EditInteractionResponse, UserId,
+};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage,
+};
+use serenity::model::application::{CommandOptionType, ResolvedOption, ResolvedValue};
+use serenity::prelude::*;
+use tabled::{settings::Style, Table, Tabled};
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+use crate::audit;
+use crate::permissions;
+use crate::servers::health::HealthState;
+use crate::servers::status::ServerState;
+use crate::servers::tracker::TrackedServer;
+use crate::servers::{
+    self, config, dependencies, format_duration, limits, state, supervisor, tracker, versions,
+};
+use crate::tasks::{dashboard, wake};
+
+fn reply(content: impl Into<String>) -> CreateInteractionResponseMessage {
+    CreateInteractionResponseMessage::new().content(content)
+}
+
+/// Value of a string option of a subcommand.
+fn option_str<'a>(options: &'a [ResolvedOption], name: &str) -> Option<&'a str> {
+    options.iter().find_map(|option| match option.value {
+        ResolvedValue::String(value) if option.name == name => Some(value),
+        _ => None,
+    })
+}
+
+/// Value of an integer option of a subcommand.
+fn option_int(options: &[ResolvedOption], name: &str) -> Option<i64> {
+    options.iter().find_map(|option| match option.value {
+        ResolvedValue::Integer(value) if option.name == name => Some(value),
+        _ => None,
+    })
+}
+
+fn list_gameservers() -> String {
+    let list = servers::discover();
+
+    if list.is_empty() {
+        return "No gameservers are available at this time.".to_string();
+    }
+
+    // assemble the list to be printed
+    let mut list_string = "The following gameservers are currently available:\n".to_string();
+    for server in list {
+        list_string.push_str("* ");
+        list_string.push_str(server.as_str());
+
+        let config = config::load(&server);
+        if let Some(game_type) = &config.game_type {
+            list_string.push_str(&format!(" ({game_type})"));
+        }
+        if let Some(version) = versions::current(&server) {
+            list_string.push_str(&format!(" [{version}]"));
+        }
+        if !config.description.is_empty() {
+            list_string.push_str(" - ");
+            list_string.push_str(&config.description);
+        }
+        list_string.push('\n');
+    }
+
+    list_string
+}
+
+async fn stop_gameserver(gameserver: &str) -> Result<(), String> {
+    let status = Command::new("bash")
+        .arg(servers::path(gameserver, "stop.sh"))
+        .status()
+        .await
+        .map_err(|why| format!("could not run stop.sh of {gameserver}: {why}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("stop.sh exited with {status}"))
+    }
+}
+
+/// Stops all running gameservers at once, used when the bot shuts down. They are saved and
+/// checked to go down like with `/gameserver stop`, only without warning players.
+pub async fn stop_all_gameservers(ctx: &Context) {
+    let mut stops = JoinSet::new();
+    for gameserver in servers::discover() {
+        if !state::get(&gameserver).running {
+            continue;
+        }
+        let ctx = ctx.clone();
+        stops.spawn(async move {
+            let mut progress = graceful::Progress::new(&ctx, None, &format!("Stopping {gameserver}..."));
+            if let Err(why) = graceful::stop(&ctx, &gameserver, "bot", 0, &mut progress).await {
+                println!("could not stop {gameserver}: {why}");
+            }
+        });
+    }
+    stops.join_all().await;
+}
+
+async fn start_gameserver(gameserver: &str) -> Result<(), String> {
+    let limits = config::load(gameserver).limits;
+    let mut command = Command::new("bash");
+    command.arg(servers::path(gameserver, "start.sh"));
+    // safe, as limits::apply only uses async-signal-safe functions. the limits are inherited by
+    // the server, even if start.sh detaches it
+    unsafe {
+        command.pre_exec(move || limits::apply(&limits));
+    }
+    let status = command
+        .status()
+        .await
+        .map_err(|why| format!("could not start {gameserver}: {why}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("start.sh exited with {status}"))
+    }
+}
+
+/// Runs start.sh of a single gameserver on behalf of `actor` and records it in the audit log.
+async fn launch(ctx: &Context, gameserver: &str, actor: &str, detail: &str) -> Result<String, String> {
+    audit::record(actor, "start", gameserver, detail);
+    // the server needs its port, which may be held to wake it
+    wake::release(ctx, gameserver).await;
+
+    let result = if config::load(gameserver).supervised {
+        supervisor::start(ctx, gameserver).await.map(Some)
+    } else {
+        start_gameserver(gameserver).await.map(|_| None)
+    };
+
+    let answer = match result {
+        Ok(pid) => {
+            state::record_start(gameserver, Some(actor), pid);
+            Ok(match pid {
+                Some(pid) => format!("Started gameserver succesfully (pid {pid})"),
+                None => "Started gameserver succesfully".to_string(),
+            })
+        }
+        Err(why) => {
+            println!("{why}");
+            Err(format!("Failed to start gameserver: {why}"))
+        }
+    };
+    // so dashboard and presence don't lag behind
+    tracker::update(ctx, gameserver).await;
+    answer
+}
+
+/// Starts a gameserver on behalf of `actor`, after starting the servers it depends on and waiting
+/// for them to be ready. Err if the gameserver itself was not started.
+pub async fn start(
+    ctx: &Context,
+    gameserver: &str,
+    actor: &str,
+    detail: &str,
+) -> Result<String, String> {
+    if !servers::exists(gameserver) {
+        return Err(format!("There is no gameserver called {gameserver}."));
+    }
+    dependencies::start_order(gameserver)?;
+    queue::fits(ctx, gameserver).await?;
+    let blocking = queue::blocking(ctx, gameserver).await;
+    if !blocking.is_empty() {
+        return Err(format!(
+            "Only {} gameserver(s) may run at once and {} is running.",
+            queue::max_running().unwrap_or(usize::MAX),
+            blocking.join(", ")
+        ));
+    }
+
+    let mut answer = String::new();
+    for dependency in dependencies::missing(ctx, gameserver).await {
+        let detail = format!("dependency of {gameserver}");
+        if let Err(why) = launch(ctx, &dependency, actor, &detail).await {
+            return Err(format!("{answer}Could not start {dependency}, which {gameserver} depends on. {why}"));
+        }
+        if let Err(why) = dependencies::wait_until_ready(ctx, &dependency).await {
+            return Err(format!("{answer}{why}, so {gameserver} was not started."));
+        }
+        answer.push_str(&format!("Started {dependency}, which {gameserver} depends on.\n"));
+    }
+
+    match launch(ctx, gameserver, actor, detail).await {
+        Ok(started) => Ok(answer + &started),
+        Err(why) => Err(answer + &why),
+    }
+}
+
+/// Starts a gameserver for a user of `/gameserver start`, starting their cooldowns if it worked.
+async fn start_for(ctx: &Context, gameserver: &str, user: UserId) -> String {
+    match start(ctx, gameserver, &user.to_string(), "command").await {
+        Ok(answer) => {
+            cooldown::record(ctx, Some(user), gameserver).await;
+            answer
+        }
+        Err(why) => why,
+    }
+}
+
+/// Starts a gameserver for `/gameserver start`. Start scripts and dependencies may take longer
+/// than discord waits for a response, so it is deferred. Responds on its own.
+async fn start_deferred(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    gameserver: &str,
+) -> Option<CreateInteractionResponseMessage> {
+    if let Err(why) = interaction.defer(&ctx.http).await {
+        println!("Cannot respond to slash command: {why}");
+        return None;
+    }
+
+    let answer = start_for(ctx, gameserver, interaction.user.id).await;
+    if let Err(why) = interaction
+        .edit_response(&ctx.http, EditInteractionResponse::new().content(answer))
+        .await
+    {
+        println!("Cannot respond to slash command: {why}");
+    }
+    None
+}
+
+#[derive(Tabled)]
+struct StatusRow {
+    name: String,
+    state: String,
+    players: String,
+    address: String,
+}
+
+/// Handles `/gameserver status` without a gameserver: probes all of them at once and shows a table.
+async fn status_all(ctx: &Context) -> String {
+    let servers = servers::discover();
+    if servers.is_empty() {
+        return "No gameservers are available at this time.".to_string();
+    }
+
+    let mut probes = JoinSet::new();
+    for (index, server) in servers.into_iter().enumerate() {
+        let ctx = ctx.clone();
+        probes.spawn(async move { (index, tracker::probe(&ctx, &server).await) });
+    }
+    let mut probed: Vec<(usize, TrackedServer)> = probes.join_all().await;
+    probed.sort_by_key(|(index, _)| *index);
+
+    let rows: Vec<StatusRow> = probed
+        .into_iter()
+        .map(|(_, tracked)| StatusRow {
+            state: match (tracked.status.state, &tracked.health) {
+                (ServerState::Running, Some(health)) => match health.state {
+                    HealthState::Healthy => "running",
+                    HealthState::Degraded => "degraded",
+                    HealthState::Down => "down",
+                },
+                (ServerState::Running, None) => "running",
+                (ServerState::Stopped, _) => "stopped",
+                (ServerState::Unknown, _) => "unknown",
+            }
+            .to_string(),
+            players: match tracked.status.state {
+                ServerState::Running => tracked.status.players_display(),
+                _ => "-".to_string(),
+            },
+            address: tracked.config.ip,
+            name: tracked.config.name,
+        })
+        .collect();
+
+    let mut table = Table::new(rows);
+    table.with(Style::markdown());
+    format!("```\n{table}\n```")
+}
+
+/// Embed showing a json status, see gameservers/README.md.
+fn status_embed(tracked: &TrackedServer) -> CreateEmbed {
+    let status = &tracked.status;
+    let colour = match status.state {
+        ServerState::Running => 0x2ecc71,
+        ServerState::Stopped => 0xe74c3c,
+        ServerState::Unknown => 0x95a5a6,
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(&tracked.config.name)
+        .colour(colour)
+        .field("State", status.state.to_string(), true)
+        .field("Players", status.players_display(), true);
+
+    if let Some(health) = &tracked.health {
+        embed = embed.field("Health", health.describe(), true);
+    }
+    if let Some(version) = versions::current(&tracked.server) {
+        embed = embed.field("Active version", version, true);
+    }
+    if !tracked.config.description.is_empty() {
+        embed = embed.description(&tracked.config.description);
+    }
+    if let Some(map) = &status.map {
+        embed = embed.field("Map", map, true);
+    }
+    if let Some(version) = &status.version {
+        embed = embed.field("Version", version, true);
+    }
+    if !tracked.config.ip.is_empty() {
+        embed = embed.field("Address", format!("`{}`", tracked.config.ip), true);
+    }
+    if let Some(uptime) = tracked.record.uptime() {
+        embed = embed.field("Uptime", format_duration(uptime), true);
+    }
+    if let Some(started_by) = tracked.record.started_by_mentions() {
+        embed = embed.field("Started by", started_by, true);
+    }
+    for (key, value) in &status.extra {
+        embed = embed.field(key, value, true);
+    }
+
+    embed
+}
+
+async fn status_gameserver(ctx: &Context, gameserver: String) -> CreateInteractionResponseMessage {
+    if !servers::exists(&gameserver) {
+        return reply(format!("There is no gameserver called {gameserver}."));
+    }
+    let tracked = tracker::probe(ctx, &gameserver).await;
+    let status = &tracked.status;
+
+    if status.structured {
+        return CreateInteractionResponseMessage::new().embed(status_embed(&tracked));
+    }
+
+    let mut answer = match status.state {
+        ServerState::Unknown => "Could not determine gameserver status".to_string(),
+        _ if !status.output.trim().is_empty() => status.output.clone(),
+        state => format!("{gameserver} is {state}"),
+    };
+    if let Some(health) = &tracked.health {
+        answer.push_str(&format!("\nHealth: {}", health.describe()));
+    }
+    if let Some(version) = versions::current(&gameserver) {
+        answer.push_str(&format!("\nActive version: {version}"));
+    }
+    if let Some(uptime) = tracked.record.uptime() {
+        answer.push_str(&format!("\nUp for {}", format_duration(uptime)));
+    }
+    if let Some(started_by) = tracked.record.started_by_mentions() {
+        answer.push_str(&format!(", started by {started_by}"));
+    }
+    reply(answer)
+}
+
+async fn create_dashboard(ctx: &Context, command: &CommandInteraction) -> String {
+    match dashboard::create(ctx, command.channel_id).await {
+        Ok(()) => "Dashboard created, it will be updated every minute.".to_string(),
+        Err(why) => {
+            println!("{why}");
+            "Failed to create the dashboard.".to_string()
+        }
+    }
+}
+
+/// Returns the response to send, or None if the subcommand responded on its own.
+pub async fn run(
+    options: &[ResolvedOption<'_>],
+    ctx: &Context,
+    interaction: &CommandInteraction,
+) -> Option<CreateInteractionResponseMessage> {
+    let user = interaction.user.id;
+
+    // requesting a vote is open to everyone
+    if let Some(ResolvedOption {
+        name: "request",
+        value: ResolvedValue::SubCommand(command),
+        ..
+    }) = options.first()
+    {
+        return Some(match command.first().map(|option| &option.value) {
+            Some(ResolvedValue::String(server)) => vote::request(ctx, interaction, server).await,
+            _ => reply("Ok, which one tho?"),
+        });
+    }
+
+    let whitelisted = permissions::is_whitelisted(user);
+
+    if let (
+        Some(ResolvedOption {
+            name: "files",
+            value: ResolvedValue::SubCommandGroup(group),
+            ..
+        }),
+        true,
+    ) = (options.first(), whitelisted)
+    {
+        return Some(match group.first() {
+            Some(subcommand) => files::run(interaction, subcommand).await,
+            None => reply("please provide a valid command"),
+        });
+    }
+
+    // disruptive actions have to be confirmed using a button first
+    if let Some(ResolvedOption {
+        name,
+        value: ResolvedValue::SubCommand(command),
+        ..
+    }) = options.first()
+    {
+        if let (Some(action), true) = (confirm::Action::from_name(name), whitelisted) {
+            let delay = option_int(command, "delay").unwrap_or(0).max(0) as u64;
+            return Some(match option_str(command, "gameserver") {
+                Some(server) => confirm::ask(ctx, action, server, delay).await,
+                None => reply("Ok, which one tho?"),
+            });
+        }
+
+        match (*name, whitelisted, option_str(command, "gameserver")) {
+            ("version", true, Some(server)) => {
+                let version = option_str(command, "name");
+                return Some(version::run(ctx, interaction, server, version).await);
+            }
+            ("get", true, Some(server)) => {
+                return Some(settings::get(interaction, server, option_str(command, "key")))
+            }
+            ("set", true, Some(server)) => {
+                return Some(
+                    match (option_str(command, "key"), option_str(command, "value")) {
+                        (Some(key), Some(value)) => settings::set(interaction, server, key, value),
+                        _ => reply("Ok, which one tho?"),
+                    },
+                )
+            }
+            _ => {}
+        }
+
+        if let ("console", true) = (*name, whitelisted) {
+            match (option_str(command, "gameserver"), option_str(command, "line")) {
+                (Some(server), Some(line)) => console::run(ctx, interaction, server, line).await,
+                _ => return Some(reply("Ok, which one tho?")),
+            }
+            return None;
+        }
+    }
+
+    Some(if whitelisted {
+        dbg!(options);
+        if let Some(ResolvedOption {
+            name,
+            value: ResolvedValue::SubCommand(command),
+            ..
+        }) = options.first()
+        {
+            // subcommand has further input
+            if let Some(subcommand) = command.first() {
+                println!("running {}", subcommand.name);
+                dbg!(subcommand);
+                dbg!(name);
+                match name.to_owned() {
+                    "list" => reply(list_gameservers()),
+                    "start" => {
+                        if let ResolvedValue::String(option) = subcommand.value {
+                            if !servers::exists(option) {
+                                return Some(reply(format!(
+                                    "There is no gameserver called {option}."
+                                )));
+                            }
+                            if let Err(why) = queue::fits(ctx, option).await {
+                                return Some(reply(why));
+                            }
+                            let blocking = queue::blocking(ctx, option).await;
+                            if let Err(why) = quota::check(user) {
+                                return Some(reply(why));
+                            }
+                            match cooldown::check(ctx, Some(user), option).await {
+                                Err(why) => reply(why),
+                                // all slots are taken, the start may be queued instead
+                                Ok(()) if !blocking.is_empty() => queue::offer(option, &blocking),
+                                Ok(()) => return start_deferred(ctx, interaction, option).await,
+                            }
+                        } else {
+                            reply("Ok, which one tho?")
+                        }
+                    }
+                    "logs" => match option_str(command, "gameserver") {
+                        Some(server) => logs::run(server, option_str(command, "archive")).await,
+                        None => reply("Ok, which one tho?"),
+                    },
+                    "status" => {
+                        if let ResolvedValue::String(option) = subcommand.value {
+                            status_gameserver(ctx, option.to_owned()).await
+                        } else {
+                            reply("Ok, which one tho?")
+                        }
+                    }
+                    _ => reply("please provide a valid command"),
+                }
+            } else {
+                // subcommand has no further input
+                if let Some(ResolvedOption { name, .. }) = options.first() {
+                    println!("no subcommands");
+                    print!("{name}");
+                    match name.to_owned() {
+                        "list" => reply(list_gameservers()),
+                        "dashboard" => reply(create_dashboard(ctx, interaction).await),
+                        "status" => reply(status_all(ctx).await),
+                        "quota" => reply(quota::show(user)).ephemeral(true),
+                        _ => reply("please provide a valid command"),
+                    }
+                } else {
+                    println!("ResolvedOption has no options and no name.");
+                    reply("Please provide a valid command")
+                }
+            }
+        } else {
+            println!("it should never get here");
+            reply("What? how?")
+        }
+    } else {
+        reply("You are not on the whitelist. Try asking a moderator or something, or use `/gameserver request` to start a vote.")
+    })
+}
+
+/// Handles clicks on buttons of gameserver messages.
+pub async fn handle_component(ctx: &Context, component: &ComponentInteraction) {
+    let custom_id = component.data.custom_id.as_str();
+
+    if let Some(vote_id) = custom_id.strip_prefix(vote::BUTTON_PREFIX) {
+        match vote_id.parse() {
+            Ok(vote_id) => vote::handle_button(ctx, component, vote_id).await,
+            Err(_) => println!("invalid vote button id {custom_id}"),
+        }
+    } else if let Some(rest) = custom_id.strip_prefix(confirm::CONFIRM_PREFIX) {
+        confirm::handle_confirm(ctx, component, rest).await;
+    } else if let Some(server) = custom_id.strip_prefix(queue::BUTTON_PREFIX) {
+        queue::handle_button(ctx, component, server).await;
+    } else if custom_id == confirm::CANCEL_ID {
+        confirm::handle_cancel(ctx, component).await;
+    }
+}
+
+pub fn register() -> CreateCommand {
+    let subcommands = vec![
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Lists all available gameservers.",
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "start",
+            "Starts a given gameserver.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "gameserver",
+            "The gameserver you want to start.",
+        )),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "stop",
+            "Stops a given gameserver.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to stop.",
+            )
+            .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "delay",
+                "Seconds to warn players in game before stopping.",
+            )
+            .min_int_value(0),
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "restart",
+            "Restarts a given gameserver.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to restart.",
+            )
+            .required(true),
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "logs",
+            "Attaches the logs of a gameserver archived on a given date, or lists the dates.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want the logs of.",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "archive",
+            "Date the logs were archived on, e.g. 2026-10-19.",
+        )),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommandGroup,
+            "files",
+            "Downloads or replaces config files of a gameserver.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "get",
+                "Sends a file of a gameserver as an attachment.",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "gameserver",
+                    "The gameserver the file belongs to.",
+                )
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "file",
+                    "Path of the file inside the gameservers folder, e.g. server.properties.",
+                )
+                .required(true),
+            ),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "put",
+                "Replaces a file of a gameserver with an attachment, backing up the old one.",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "gameserver",
+                    "The gameserver the file belongs to.",
+                )
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "file",
+                    "Path of the file inside the gameservers folder, e.g. server.properties.",
+                )
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Attachment,
+                    "attachment",
+                    "The new file.",
+                )
+                .required(true),
+            ),
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "get",
+            "Shows a setting of a gameserver, or all of them.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to see the settings of.",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "key",
+            "The setting, e.g. difficulty.",
+        )),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "set",
+            "Changes a setting in the config file of a gameserver.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to change a setting of.",
+            )
+            .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "key",
+                "The setting, e.g. difficulty.",
+            )
+            .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "value", "The new value.")
+                .required(true),
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "version",
+            "Switches a stopped gameserver to another installed version, or lists them.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to switch.",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "name",
+            "The version to switch to, a folder in its versions folder.",
+        )),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "quota",
+            "Shows how much gameserver time you used and have left this month.",
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "console",
+            "Sends a line to the console of a given gameserver and shows its output.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to send the line to.",
+            )
+            .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "line",
+                "The console command.",
+            )
+            .required(true),
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "status",
+            "Prints information on a given gameserver, or a summary of all of them.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "gameserver",
+            "The gameserver you want to get the status of.",
+        )),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "dashboard",
+            "Posts a dashboard of all gameservers in this channel, which updates every minute.",
+        ),
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "request",
+            "Starts a vote to start a given gameserver. Can be used by anyone.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "gameserver",
+                "The gameserver you want to start.",
+            )
+            .required(true),
+        ),
+    ];
+
+    CreateCommand::new("gameserver")
+        .description("control gameservers")
+        .set_options(subcommands)
+}
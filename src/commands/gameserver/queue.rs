@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponseMessage, CreateMessage, UserId,
+};
+use serenity::prelude::*;
+
+use super::confirm::update;
+use super::{quota, reply};
+use crate::servers::status::ServerState;
+use crate::servers::{self, config, dependencies, tracker};
+use crate::{audit, notifications, permissions};
+
+/// Prefix of the custom id of queue buttons, followed by the server.
+pub const BUTTON_PREFIX: &str = "queue:";
+
+/// How often the queue checks for a free slot.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A start waiting for a free slot.
+pub struct QueuedStart {
+    server: String,
+    requester: UserId,
+}
+
+/// Starts waiting for a free slot, first come first served.
+pub struct StartQueue;
+
+impl TypeMapKey for StartQueue {
+    type Value = Arc<Mutex<VecDeque<QueuedStart>>>;
+}
+
+async fn queue(ctx: &Context) -> Arc<Mutex<VecDeque<QueuedStart>>> {
+    let data = ctx.data.read().await;
+    data.get::<StartQueue>()
+        .expect("StartQueue should be in the client data")
+        .clone()
+}
+
+/// How many gameservers may run at once, set by MAX_RUNNING_SERVERS. None means no limit.
+pub fn max_running() -> Option<usize> {
+    env::var("MAX_RUNNING_SERVERS")
+        .ok()
+        .and_then(|max| max.parse().ok())
+}
+
+/// Number of slots starting `server` takes, including the dependencies which would be started
+/// with it.
+async fn slots_needed(ctx: &Context, server: &str) -> usize {
+    let tracked = tracker::snapshot(ctx).await;
+    let is_running = |name: &str| {
+        tracked
+            .iter()
+            .any(|t| t.server == name && dependencies::counts_as_running(name, t.status.state))
+    };
+    let missing = dependencies::start_order(server)
+        .unwrap_or_default()
+        .iter()
+        .filter(|dependency| !is_running(dependency))
+        .count();
+    missing + 1
+}
+
+/// Err if `server` and the dependencies started with it need more slots than may run at once,
+/// so it could never start.
+pub async fn fits(ctx: &Context, server: &str) -> Result<(), String> {
+    let Some(max) = max_running() else {
+        return Ok(());
+    };
+    let needed = slots_needed(ctx, server).await;
+    if needed > max {
+        return Err(format!(
+            "Only {max} gameserver(s) may run at once, but {server} needs {needed} with its dependencies."
+        ));
+    }
+    Ok(())
+}
+
+/// Names of the running servers taking up the slots `server` and its dependencies need, empty if
+/// it may start.
+pub async fn blocking(ctx: &Context, server: &str) -> Vec<String> {
+    let Some(max) = max_running() else {
+        return vec![];
+    };
+
+    let running: Vec<String> = tracker::snapshot(ctx)
+        .await
+        .into_iter()
+        .filter(|tracked| tracked.server != server && tracked.status.state == ServerState::Running)
+        .map(|tracked| tracked.config.name)
+        .collect();
+
+    if running.len() + slots_needed(ctx, server).await > max {
+        running
+    } else {
+        vec![]
+    }
+}
+
+/// Tells the user all slots are taken, offering to queue the start. Only visible to them.
+pub fn offer(server: &str, blocking: &[String]) -> CreateInteractionResponseMessage {
+    let name = config::load(server).name;
+    let button =
+        CreateActionRow::Buttons(vec![CreateButton::new(format!("{BUTTON_PREFIX}{server}"))
+            .label("Queue start")
+            .style(ButtonStyle::Primary)]);
+
+    reply(format!(
+        "Only {} gameserver(s) may run at once and {} is running.\nQueue **{name}**? It starts as soon as a slot is free.",
+        max_running().unwrap_or(0),
+        blocking.join(", ")
+    ))
+    .components(vec![button])
+    .ephemeral(true)
+}
+
+/// Handles a click on a queue button.
+pub async fn handle_button(ctx: &Context, component: &ComponentInteraction, server: &str) {
+    let user = component.user.id;
+    // the whitelist may have changed since the offer was made
+    if !permissions::is_whitelisted(user) {
+        update(ctx, component, "You are not on the whitelist.").await;
+        return;
+    }
+    // the gameserver may have been removed since the offer was made
+    if !servers::exists(server) {
+        update(
+            ctx,
+            component,
+            &format!("There is no gameserver called {server}."),
+        )
+        .await;
+        return;
+    }
+
+    let name = config::load(server).name;
+    let queue = queue(ctx).await;
+    let mut queue = queue.lock().await;
+    let content = if queue.iter().any(|queued| queued.server == server) {
+        format!("**{name}** is already queued.")
+    } else {
+        queue.push_back(QueuedStart {
+            server: server.to_string(),
+            requester: user,
+        });
+        audit::record(&user.to_string(), "queue", server, "");
+        format!(
+            "Queued **{name}** at position {}. You will get a message once it starts.",
+            queue.len()
+        )
+    };
+    drop(queue);
+
+    update(ctx, component, &content).await;
+}
+
+/// Tells the requester of a queued start how it went, in a direct message or the notification
+/// channel if they don't accept those.
+async fn notify(ctx: &Context, requester: UserId, content: String) {
+    let message = CreateMessage::new().content(&content);
+    if let Err(why) = requester.direct_message(&ctx.http, message).await {
+        println!("could not message {requester}: {why}");
+        notifications::mention(ctx, requester, &content).await;
+    }
+}
+
+/// Starts queued servers as soon as a slot is free.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let queue = queue(&ctx).await;
+            let (next, refusal) = {
+                let mut queue = queue.lock().await;
+                let Some(next) = queue.front() else {
+                    continue;
+                };
+                // dependencies may have been added while it waited, it would block the queue
+                let refusal = fits(&ctx, &next.server).await.err();
+                if refusal.is_none() && !blocking(&ctx, &next.server).await.is_empty() {
+                    continue;
+                }
+                (queue.pop_front().expect("queue has a front"), refusal)
+            };
+
+            let name = config::load(&next.server).name;
+            let answer = if let Some(why) = refusal {
+                why
+            } else if tracker::probe(&ctx, &next.server).await.status.state == ServerState::Running
+            {
+                "It was started in the meantime.".to_string()
+            } else if let Err(why) = quota::check(next.requester) {
+                // the quota may have run out while waiting
+                why
+            } else {
+                let actor = next.requester.to_string();
+                match super::start(&ctx, &next.server, &actor, "queued").await {
+                    Ok(answer) | Err(answer) => answer,
+                }
+            };
+            notify(
+                &ctx,
+                next.requester,
+                format!("Your queued start of **{name}**: {answer}"),
+            )
+            .await;
+        }
+    });
+}
@@ -0,0 +1,57 @@
+use std::env;
+use std::time::Duration;
+
+use serenity::all::UserId;
+
+use crate::permissions;
+use crate::servers::{format_duration, usage};
+
+/// Gameserver runtime each user may start per month, set by MONTHLY_QUOTA_HOURS. None means no
+/// quota.
+pub fn monthly_quota() -> Option<Duration> {
+    env::var("MONTHLY_QUOTA_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse::<u64>().ok())
+        .map(|hours| Duration::from_secs(hours * 3600))
+}
+
+/// Checks whether a user has runtime left this month to start a server. Admins are exempt.
+pub fn check(user: UserId) -> Result<(), String> {
+    let Some(quota) = monthly_quota() else {
+        return Ok(());
+    };
+    if permissions::is_admin(user) {
+        return Ok(());
+    }
+
+    let used = usage::used_this_month(&user.to_string());
+    if used >= quota {
+        return Err(format!(
+            "You used up your {} of gameserver time this month. It resets <t:{}:R>.",
+            format_duration(quota),
+            usage::next_month_start()
+        ));
+    }
+    Ok(())
+}
+
+/// Handles `/gameserver quota`, showing how much runtime the user used and has left this month.
+pub fn show(user: UserId) -> String {
+    let used = usage::used_this_month(&user.to_string());
+    let mut answer = format!(
+        "Gameservers you started ran for {} this month.",
+        format_duration(used)
+    );
+
+    match monthly_quota() {
+        Some(_) if permissions::is_admin(user) => answer.push_str("\nAdmins have no quota."),
+        Some(quota) => answer.push_str(&format!(
+            "\nRemaining: {} of {}, resets <t:{}:R>.",
+            format_duration(quota.saturating_sub(used)),
+            format_duration(quota),
+            usage::next_month_start()
+        )),
+        None => answer.push_str("\nThere is no quota."),
+    }
+    answer
+}
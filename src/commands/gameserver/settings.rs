@@ -0,0 +1,71 @@
+use serenity::all::{CommandInteraction, CreateInteractionResponseMessage};
+
+use super::files::check_access;
+use super::reply;
+use crate::audit;
+use crate::notifications;
+use crate::servers::{config, settings};
+
+/// Leaves room for the code block in discords 2000 character limit.
+const MAX_LIST: usize = 1900;
+
+/// Lists all settings, as many as fit into a message.
+fn list(server: &str, config: &config::ServerConfig) -> Result<String, String> {
+    let all = settings::list(server, &config.settings)?;
+    if all.is_empty() {
+        return Ok(format!("{} has no settings.", config.name));
+    }
+
+    let several_files = config.settings.len() > 1;
+    let mut listing = String::new();
+    for (file, key, value) in all {
+        let line = match several_files {
+            true => format!("{file}:{key} = {value}\n"),
+            false => format!("{key} = {value}\n"),
+        };
+        if listing.len() + line.len() > MAX_LIST {
+            listing.push_str("...\n");
+            break;
+        }
+        listing.push_str(&line);
+    }
+    Ok(format!(
+        "Settings of {}:\n```\n{}```",
+        config.name,
+        notifications::escape_code_block(&listing)
+    ))
+}
+
+/// Handles `/gameserver get`, showing one setting or all of them.
+pub fn get(
+    interaction: &CommandInteraction,
+    server: &str,
+    key: Option<&str>,
+) -> CreateInteractionResponseMessage {
+    let result = check_access(interaction, server).and_then(|config| match key {
+        None => list(server, &config),
+        Some(key) => Ok(match settings::get(server, &config.settings, key)? {
+            Some(value) => format!("{key} = `{value}`"),
+            None => format!("{key} is not set."),
+        }),
+    });
+    reply(result.unwrap_or_else(|why| why)).ephemeral(true)
+}
+
+/// Handles `/gameserver set`, changing a setting in the game config file.
+pub fn set(
+    interaction: &CommandInteraction,
+    server: &str,
+    key: &str,
+    value: &str,
+) -> CreateInteractionResponseMessage {
+    let result = check_access(interaction, server).and_then(|config| {
+        let file = settings::set(server, &config.settings, key, value)?;
+        // only the key, values may be passwords
+        audit::record(&interaction.user.id.to_string(), "set", server, key);
+        Ok(format!(
+            "Set {key} to `{value}` in {file}. Most games only pick it up after a restart."
+        ))
+    });
+    reply(result.unwrap_or_else(|why| why)).ephemeral(true)
+}
@@ -0,0 +1,58 @@
+use serenity::all::{CommandInteraction, CreateInteractionResponseMessage};
+use serenity::prelude::*;
+
+use super::reply;
+use crate::audit;
+use crate::servers::status::ServerState;
+use crate::servers::{self, tracker, versions};
+
+/// Lists the installed versions, marking the active one.
+fn list(server: &str) -> String {
+    let available = versions::available(server);
+    if available.is_empty() {
+        return format!(
+            "{server} has no versions, they go into its {} folder.",
+            versions::VERSIONS_DIR
+        );
+    }
+
+    let current = versions::current(server);
+    let lines: Vec<String> = available
+        .into_iter()
+        .map(|version| match current.as_ref() == Some(&version) {
+            true => format!("* **{version}** (active)"),
+            false => format!("* {version}"),
+        })
+        .collect();
+    format!("Versions of {server}:\n{}", lines.join("\n"))
+}
+
+/// Handles `/gameserver version`, switching to another version or listing them.
+pub async fn run(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    server: &str,
+    version: Option<&str>,
+) -> CreateInteractionResponseMessage {
+    if !servers::exists(server) {
+        return reply(format!("There is no gameserver called {server}."));
+    }
+    let Some(version) = version else {
+        return reply(list(server));
+    };
+
+    // the files of a running server must not change under it
+    let tracked = tracker::probe(ctx, server).await;
+    if tracked.status.state == ServerState::Running {
+        return reply(format!(
+            "{} is running, stop it before switching versions.",
+            tracked.config.name
+        ));
+    }
+
+    audit::record(&interaction.user.id.to_string(), "version", server, version);
+    reply(match versions::switch(server, version) {
+        Ok(()) => format!("Switched {} to {version}.", tracked.config.name),
+        Err(why) => why,
+    })
+}
@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::all::{
+    ButtonStyle, CommandInteraction, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, EditInteractionResponse, UserId,
+};
+use serenity::prelude::*;
+
+use super::{cooldown, reply};
+use crate::servers::state::now;
+use crate::servers::status::ServerState;
+use crate::servers::{self, config, tracker};
+
+/// Prefix of the custom id of vote buttons, followed by the vote id.
+pub const BUTTON_PREFIX: &str = "vote:";
+
+pub struct Vote {
+    server: String,
+    name: String,
+    requester: UserId,
+    voters: HashSet<UserId>,
+    required: usize,
+    /// Unix timestamp the vote ends at.
+    ends_at: u64,
+}
+
+impl Vote {
+    fn message(&self) -> String {
+        format!(
+            "<@{}> wants to start **{}**. Votes: {}/{}, vote ends <t:{}:R>.",
+            self.requester,
+            self.name,
+            self.voters.len(),
+            self.required,
+            self.ends_at
+        )
+    }
+}
+
+/// Open votes by id.
+pub struct Votes;
+
+impl TypeMapKey for Votes {
+    type Value = Arc<Mutex<HashMap<u64, Vote>>>;
+}
+
+async fn votes(ctx: &Context) -> Arc<Mutex<HashMap<u64, Vote>>> {
+    let data = ctx.data.read().await;
+    data.get::<Votes>()
+        .expect("Votes should be in the client data")
+        .clone()
+}
+
+/// Starts a gameserver once enough members voted, if the server cooldown allows it.
+async fn start_voted(ctx: &Context, server: &str, actor: &str, detail: &str) -> String {
+    if let Err(why) = cooldown::check(ctx, None, server).await {
+        return why;
+    }
+    match super::start(ctx, server, actor, detail).await {
+        Ok(answer) => {
+            cooldown::record(ctx, None, server).await;
+            answer
+        }
+        Err(why) => why,
+    }
+}
+
+fn vote_button(vote_id: u64) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![CreateButton::new(format!("{BUTTON_PREFIX}{vote_id}"))
+        .label("Vote")
+        .style(ButtonStyle::Primary)])
+}
+
+/// Handles `/gameserver request`, which anyone may use to open a vote to start a gameserver.
+pub async fn request(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    server: &str,
+) -> CreateInteractionResponseMessage {
+    if !servers::exists(server) {
+        return reply(format!("There is no gameserver called {server}."));
+    }
+
+    let config = config::load(server);
+    if !config.vote.enabled {
+        return reply(format!("{} can't be started by vote.", config.name));
+    }
+    if tracker::probe(ctx, server).await.status.state == ServerState::Running {
+        return reply(format!("{} is already running.", config.name));
+    }
+
+    let vote = Vote {
+        server: server.to_string(),
+        name: config.name,
+        requester: interaction.user.id,
+        // requesting counts as voting
+        voters: HashSet::from([interaction.user.id]),
+        required: config.vote.required,
+        ends_at: now() + config.vote.window,
+    };
+
+    if vote.voters.len() >= vote.required {
+        let actor = interaction.user.id.to_string();
+        return reply(start_voted(ctx, server, &actor, "requested").await);
+    }
+
+    let vote_id = interaction.id.get();
+    let response = reply(vote.message()).components(vec![vote_button(vote_id)]);
+    votes(ctx).await.lock().await.insert(vote_id, vote);
+
+    // close the vote once the window has passed
+    let window = Duration::from_secs(config.vote.window);
+    let ctx = ctx.clone();
+    let interaction = interaction.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(window).await;
+
+        let Some(vote) = votes(&ctx).await.lock().await.remove(&vote_id) else {
+            return; // vote passed already
+        };
+        let content = format!(
+            "The vote to start **{}** has ended without enough votes ({}/{}).",
+            vote.name,
+            vote.voters.len(),
+            vote.required
+        );
+        // fails if the interaction token expired (after 15 minutes), the button then tells voters.
+        if let Err(why) = interaction
+            .edit_response(
+                &ctx.http,
+                EditInteractionResponse::new()
+                    .content(content)
+                    .components(vec![]),
+            )
+            .await
+        {
+            println!("Cannot close vote: {why}");
+        }
+    });
+
+    response
+}
+
+/// Handles a click on a vote button.
+pub async fn handle_button(ctx: &Context, component: &ComponentInteraction, vote_id: u64) {
+    let votes = votes(ctx).await;
+    let mut open_votes = votes.lock().await;
+
+    let Some(vote) = open_votes.get_mut(&vote_id) else {
+        let response = CreateInteractionResponse::UpdateMessage(
+            reply("This vote has ended.").components(vec![]),
+        );
+        if let Err(why) = component.create_response(&ctx.http, response).await {
+            println!("Cannot respond to vote: {why}");
+        }
+        return;
+    };
+
+    if vote.ends_at <= now() {
+        open_votes.remove(&vote_id);
+        let response = CreateInteractionResponse::UpdateMessage(
+            reply("This vote has ended.").components(vec![]),
+        );
+        if let Err(why) = component.create_response(&ctx.http, response).await {
+            println!("Cannot respond to vote: {why}");
+        }
+        return;
+    }
+
+    if !vote.voters.insert(component.user.id) {
+        let response =
+            CreateInteractionResponse::Message(reply("You already voted.").ephemeral(true));
+        if let Err(why) = component.create_response(&ctx.http, response).await {
+            println!("Cannot respond to vote: {why}");
+        }
+        return;
+    }
+
+    if vote.voters.len() < vote.required {
+        let response = CreateInteractionResponse::UpdateMessage(reply(vote.message()));
+        if let Err(why) = component.create_response(&ctx.http, response).await {
+            println!("Cannot respond to vote: {why}");
+        }
+        return;
+    }
+
+    // enough votes, start the server
+    let vote = open_votes.remove(&vote_id).expect("vote was just voted on");
+    drop(open_votes);
+
+    let voters: Vec<String> = vote.voters.iter().map(|v| format!("<@{v}>")).collect();
+    let content = format!(
+        "Starting **{}**, voted by {}.",
+        vote.name,
+        voters.join(", ")
+    );
+    let response = CreateInteractionResponse::UpdateMessage(reply(&content).components(vec![]));
+    if let Err(why) = component.create_response(&ctx.http, response).await {
+        println!("Cannot respond to vote: {why}");
+    }
+
+    let actor: Vec<String> = vote.voters.iter().map(|v| v.to_string()).collect();
+    let answer = start_voted(ctx, &vote.server, &actor.join(","), "vote").await;
+
+    if let Err(why) = component
+        .edit_response(
+            &ctx.http,
+            EditInteractionResponse::new().content(format!("{content}\n{answer}")),
+        )
+        .await
+    {
+        println!("Cannot respond to vote: {why}");
+    }
+}
@@ -1,18 +1,38 @@
+use std::collections::{HashMap, VecDeque};
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 
 use commands::gameserver;
-use dotenv;
-use serenity::all::EditInteractionResponse;
+use serenity::all::{EditInteractionResponse, Message};
 use serenity::async_trait;
 use serenity::builder::{CreateInteractionResponse, CreateInteractionResponseMessage};
-use serenity::model::application::{Command, Interaction};
+use serenity::model::application::Interaction;
 use serenity::model::gateway::Ready;
 use serenity::model::id::GuildId;
 use serenity::prelude::*;
-
+use commands::gameserver::cooldown::Cooldowns;
+use commands::gameserver::queue::StartQueue;
+use commands::gameserver::vote::Votes;
+use servers::health::Health;
+use servers::supervisor::Supervisor;
+use servers::tracker::Tracker;
+use tasks::wake::Wake;
+use tokio::signal::unix::{signal, SignalKind};
+
+mod audit;
 mod commands;
-
-struct Handler;
+mod notifications;
+mod permissions;
+mod servers;
+mod tasks;
+
+struct Handler {
+    // ready is called again on reconnects, background tasks must only be started once.
+    tasks_started: AtomicBool,
+    // the first context, used to stop gameservers on shutdown.
+    ctx: Arc<OnceLock<Context>>,
+}
 
 #[async_trait]
 impl EventHandler for Handler {
@@ -41,13 +61,21 @@ impl EventHandler for Handler {
 
                     Some(result.await)
                 },
-                "gameserver" => Some(gameserver::run(&command.data.options(), command.user.id)),
+                "gameserver" => {
+                    let data = gameserver::run(&command.data.options(), &ctx, &command).await;
+                    if let Some(data) = data {
+                        let builder = CreateInteractionResponse::Message(data);
+                        if let Err(why) = command.create_response(&ctx.http, builder).await {
+                            println!("Cannot respond to slash command: {why}");
+                        }
+                    }
+                    None
+                }
                 _ => Some("not implemented :(".to_string()),
             };
 
             if defered {
                 if let Some(content) = content {
-                    let data = CreateInteractionResponseMessage::new().content(&content);
                     if let Err(why) = command.edit_response(&ctx.http, EditInteractionResponse::new().content(content)).await {
                         println!("Cannot respond to slash command: {why}");
                     }
@@ -59,9 +87,15 @@ impl EventHandler for Handler {
                     println!("Cannot respond to slash command: {why}");
                 }
             }
+        } else if let Interaction::Component(component) = interaction {
+            gameserver::handle_component(&ctx, &component).await;
         }
     }
 
+    async fn message(&self, ctx: Context, msg: Message) {
+        tasks::chat_bridge::relay(&ctx, &msg).await;
+    }
+
     async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
 
@@ -86,6 +120,21 @@ impl EventHandler for Handler {
 
         println!("I now have the following guild slash commands: {commands:#?}");
 
+        self.ctx.set(ctx.clone()).ok();
+
+        if !self.tasks_started.swap(true, Ordering::SeqCst) {
+            // the first refresh happens right away and reconciles the state file (cache/servers.json)
+            // with what is actually running
+            servers::tracker::spawn(ctx.clone());
+            servers::health::spawn(ctx.clone());
+            tasks::dashboard::spawn(ctx.clone());
+            tasks::presence::spawn(ctx.clone());
+            tasks::log_rotation::spawn();
+            tasks::log_watcher::spawn(ctx.clone());
+            gameserver::queue::spawn(ctx.clone());
+            tasks::wake::spawn(ctx.clone());
+        }
+
         // let guild_command =
         //     Command::create_global_command(&ctx.http, commands::wonderful_command::register())
         //         .await;
@@ -94,21 +143,78 @@ impl EventHandler for Handler {
     }
 }
 
+/// Waits for SIGINT (ctrl-c) or SIGTERM.
+async fn shutdown_signal() {
+    let mut sigterm = signal(SignalKind::terminate()).expect("failed to listen for SIGTERM");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = sigterm.recv() => {},
+    }
+}
+
+/// Whether all gameservers should be stopped when the bot shuts down, set by
+/// STOP_SERVERS_ON_SHUTDOWN.
+fn stop_servers_on_shutdown() -> bool {
+    env::var("STOP_SERVERS_ON_SHUTDOWN")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+}
+
+/// Whether any gameserver mirrors its chat into discord.
+fn chat_configured() -> bool {
+    servers::discover()
+        .iter()
+        .any(|server| servers::config::load(server).chat.is_some())
+}
+
 #[tokio::main]
 async fn main() {
     dotenv::dotenv().ok();
     // Configure the client with your Discord bot token in the environment.
     let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
     // Set gateway intents, which decides what events the bot will be notified about
-    let intents = GatewayIntents::DIRECT_MESSAGES;
+    let mut intents = GatewayIntents::DIRECT_MESSAGES;
+    // guild messages and their content are only needed for the chat bridge. MESSAGE_CONTENT is
+    // privileged, the gateway refuses bots which didn't enable it in the developer portal.
+    if chat_configured() {
+        intents |= GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+    }
 
     // Create a new instance of the Client, logging in as a bot. This will automatically prepend
     // your bot token with "Bot ", which is a requirement by Discord for bot users.
+    let ready_ctx = Arc::new(OnceLock::new());
     let mut client = Client::builder(&token, intents)
-        .event_handler(Handler)
+        .event_handler(Handler {
+            tasks_started: AtomicBool::new(false),
+            ctx: ready_ctx.clone(),
+        })
+        .type_map_insert::<Tracker>(Arc::new(RwLock::new(HashMap::new())))
+        .type_map_insert::<Health>(Arc::new(RwLock::new(HashMap::new())))
+        .type_map_insert::<Supervisor>(Arc::new(Mutex::new(HashMap::new())))
+        .type_map_insert::<Votes>(Arc::new(Mutex::new(HashMap::new())))
+        .type_map_insert::<Cooldowns>(Arc::new(Mutex::new(Default::default())))
+        .type_map_insert::<Wake>(Arc::new(Mutex::new(Default::default())))
+        .type_map_insert::<StartQueue>(Arc::new(Mutex::new(VecDeque::new())))
         .await
         .expect("Err creating client");
 
+    // Shut down cleanly on ctrl-c or SIGTERM (e.g. from systemd or docker).
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        println!("Shutting down.");
+
+        if stop_servers_on_shutdown() {
+            match ready_ctx.get() {
+                Some(ctx) => gameserver::stop_all_gameservers(ctx).await,
+                None => println!("Not connected yet, leaving gameservers running."),
+            }
+        }
+        audit::flush();
+
+        shard_manager.shutdown_all().await;
+    });
+
     // Finally, start a single shard, and start listening to events.
     //
     // Shards will automatically attempt to reconnect, and will perform exponential backoff until
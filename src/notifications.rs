@@ -0,0 +1,45 @@
+use std::env;
+
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, UserId};
+use serenity::prelude::*;
+
+/// Channel for messages the bot posts on its own, configured by NOTIFICATION_CHANNEL_ID.
+pub fn channel() -> Option<ChannelId> {
+    let id = env::var("NOTIFICATION_CHANNEL_ID").ok()?;
+    match id.parse() {
+        Ok(id) => Some(ChannelId::new(id)),
+        Err(_) => {
+            println!("NOTIFICATION_CHANNEL_ID must be an integer");
+            None
+        }
+    }
+}
+
+/// Breaks up ``` in text put into a code block, which would end it early.
+pub fn escape_code_block(text: &str) -> String {
+    // a zero width space between the backticks
+    text.replace("```", "`\u{200b}``")
+}
+
+async fn post(ctx: &Context, content: &str, mentions: CreateAllowedMentions) {
+    if let Some(channel_id) = channel() {
+        let message = CreateMessage::new()
+            .content(content)
+            .allowed_mentions(mentions);
+        if let Err(why) = channel_id.send_message(&ctx.http, message).await {
+            println!("Cannot send notification: {why}");
+        }
+    }
+}
+
+/// Posts a message into the notification channel, if one is configured.
+/// Mentions don't ping anyone, as messages may contain player names and server output.
+pub async fn send(ctx: &Context, content: &str) {
+    post(ctx, content, CreateAllowedMentions::new()).await;
+}
+
+/// Posts a message into the notification channel mentioning `user`, pinging only them.
+pub async fn mention(ctx: &Context, user: UserId, content: &str) {
+    let mentions = CreateAllowedMentions::new().users(vec![user]);
+    post(ctx, &format!("<@{user}> {content}"), mentions).await;
+}
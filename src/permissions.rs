@@ -0,0 +1,41 @@
+use std::fs;
+
+use serenity::all::UserId;
+
+use crate::servers::config::ServerConfig;
+
+/// Users allowed to control gameservers.
+const WHITELIST: &str = "gameservers/whitelist";
+/// Users exempt from limits like cooldowns. Admins are not automatically whitelisted.
+const ADMINS: &str = "gameservers/admins";
+
+/// Reads a file containing one user id per line, lines starting with # are comments.
+fn read_user_list(path: &str) -> Vec<String> {
+    let mut users: Vec<String> = vec![];
+    if let Ok(file) = fs::read_to_string(path) {
+        for line in file.lines() {
+            if !line.starts_with('#') {
+                users.push(line.trim().to_string());
+            }
+        }
+    }
+    users
+}
+
+pub fn is_whitelisted(user: UserId) -> bool {
+    read_user_list(WHITELIST).contains(&user.to_string())
+}
+
+pub fn is_admin(user: UserId) -> bool {
+    read_user_list(ADMINS).contains(&user.to_string())
+}
+
+/// Whether a whitelisted user may send console commands to a server.
+pub fn may_use_console(user: UserId, config: &ServerConfig) -> bool {
+    is_admin(user) || config.permissions.console.contains(&user.to_string())
+}
+
+/// Whether a whitelisted user may download and replace the files of a server.
+pub fn may_edit_files(user: UserId, config: &ServerConfig) -> bool {
+    is_admin(user) || config.permissions.files.contains(&user.to_string())
+}
@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::Deserialize;
+use serde_json::Value;
+use serenity::all::ChannelId;
+
+use super::{path, presets};
+
+/// Contents of a gameservers config.json.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ServerConfig {
+    /// Display name, the folder name is used if this is missing.
+    #[serde(default)]
+    pub name: String,
+    /// Game preset providing defaults, e.g. "minecraft". See presets.rs.
+    #[serde(rename = "type")]
+    pub game_type: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    /// Address players use to connect, e.g. "example.com:25565".
+    #[serde(default)]
+    pub ip: String,
+    /// Where the server writes its log and how to recognise joins and leaves in it.
+    pub log: Option<LogConfig>,
+    /// Run start.sh as a child of the bot instead of expecting it to detach (e.g. using screen).
+    /// Its output is written to console.log and console input is sent to its stdin.
+    #[serde(default)]
+    pub supervised: bool,
+    /// How console commands are sent to the server.
+    #[serde(default)]
+    pub console: ConsoleConfig,
+    /// Mirrors the in-game chat into a discord channel and back.
+    pub chat: Option<ChatConfig>,
+    /// Lets members who are not whitelisted start the server by vote.
+    #[serde(default)]
+    pub vote: VoteConfig,
+    /// Limits how often the server can be started, stopped or restarted.
+    #[serde(default)]
+    pub cooldowns: CooldownConfig,
+    /// What happens before the server is stopped.
+    #[serde(default)]
+    pub stop: StopConfig,
+    /// Who may do what with this server, beyond the whitelist.
+    #[serde(default)]
+    pub permissions: PermissionConfig,
+    /// Resource limits applied to the server process when the bot starts it.
+    #[serde(default)]
+    pub limits: LimitConfig,
+    /// Checks telling whether a running server actually works.
+    pub health: Option<HealthConfig>,
+    /// Protocol used to ask the server for its status, if there is no status.sh.
+    pub query: Option<QueryConfig>,
+    /// When log files are rotated and how long the archived logs are kept.
+    #[serde(default)]
+    pub rotation: RotationConfig,
+    /// Files which may be downloaded and replaced using `/gameserver files`, relative to the
+    /// servers folder, e.g. "server.properties".
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Game config files whose values can be read and changed using `/gameserver get` and `set`.
+    #[serde(default)]
+    pub settings: Vec<SettingsFile>,
+    /// Servers (folder names) which are started before this one, e.g. a proxy.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Seconds to wait for the server to run when it is started as a dependency.
+    #[serde(default = "default_ready_timeout")]
+    pub ready_timeout: u64,
+    /// Starts the server when someone tries to connect while it is stopped.
+    pub wake: Option<WakeConfig>,
+}
+
+fn default_ready_timeout() -> u64 {
+    120
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct LogConfig {
+    /// Path of the log file, relative to the servers folder.
+    pub file: String,
+    /// Regex matching a join line. The player is taken from the `player` group, or the first group.
+    pub join_pattern: Option<String>,
+    /// Regex matching a leave line, same rules as `join_pattern`.
+    pub leave_pattern: Option<String>,
+    /// A leave followed by a join within this many seconds is treated as a reconnect and not announced.
+    #[serde(default = "default_reconnect_window")]
+    pub reconnect_window: u64,
+}
+
+fn default_reconnect_window() -> u64 {
+    60
+}
+
+/// Fills in the defaults of the preset given by the `type` field, if there is one.
+fn with_preset(config_path: &str, json: Value) -> Value {
+    let Some(game_type) = json.get("type").and_then(Value::as_str) else {
+        return json;
+    };
+    match presets::preset(game_type) {
+        Some(mut preset) => {
+            presets::merge(&mut preset, json);
+            preset
+        }
+        None => {
+            println!("unknown type {game_type} in {config_path}");
+            json
+        }
+    }
+}
+
+/// Loads the config.json of a gameserver. Missing or broken configs result in a default config,
+/// so a server without one is still usable.
+pub fn load(server: &str) -> ServerConfig {
+    let config_path = path(server, "config.json");
+
+    let mut config: ServerConfig = match fs::read_to_string(&config_path) {
+        Ok(content) => serde_json::from_str::<Value>(&content)
+            .map(|json| with_preset(&config_path, json))
+            .and_then(serde_json::from_value)
+            .unwrap_or_else(|why| {
+                println!("could not parse {config_path}: {why}");
+                ServerConfig::default()
+            }),
+        Err(_) => ServerConfig::default(),
+    };
+
+    if config.name.is_empty() {
+        config.name = server.to_string();
+    }
+
+    config
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ConsoleConfig {
+    pub rcon: Option<RconConfig>,
+    /// Shell command typing a line into the console, the line is passed as $1.
+    /// e.g. `screen -S example_game -X stuff "$1"$'\\n'`
+    pub command: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RconConfig {
+    /// e.g. "127.0.0.1:25575"
+    pub address: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatConfig {
+    pub channel_id: ChannelId,
+    /// Regex matching a chat line in the log, with `player` and `message` groups.
+    pub pattern: String,
+    /// Console command sending a discord message into the game, `{user}` and `{message}` are replaced.
+    #[serde(default = "default_say_format")]
+    pub say_format: String,
+}
+
+fn default_say_format() -> String {
+    "say [{user}] {message}".to_string()
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct VoteConfig {
+    /// Off unless the server opts in.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Number of distinct members needed to start the server.
+    #[serde(default = "default_votes_required")]
+    pub required: usize,
+    /// Seconds a vote stays open.
+    #[serde(default = "default_vote_window")]
+    pub window: u64,
+}
+
+impl Default for VoteConfig {
+    fn default() -> Self {
+        VoteConfig {
+            enabled: false,
+            required: default_votes_required(),
+            window: default_vote_window(),
+        }
+    }
+}
+
+fn default_votes_required() -> usize {
+    3
+}
+
+fn default_vote_window() -> u64 {
+    600
+}
+
+/// Cooldowns in seconds, 0 disables them.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct CooldownConfig {
+    /// Time before the same user may control the server again.
+    #[serde(default)]
+    pub user: u64,
+    /// Time before anyone may control the server again.
+    #[serde(default)]
+    pub server: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct StopConfig {
+    /// Seconds before the stop at which players are warned, if the stop is delayed.
+    #[serde(default = "default_stop_warnings")]
+    pub warnings: Vec<u64>,
+    /// Console command warning the players, `{time}` is replaced with the time left.
+    #[serde(default = "default_warn_format")]
+    pub warn_format: String,
+    /// Console command saving the game, run right before stopping.
+    pub save_command: Option<String>,
+    /// Seconds to wait for the save to finish.
+    #[serde(default = "default_save_wait")]
+    pub save_wait: u64,
+    /// Console command stopping the server, used if there is no stop.sh.
+    pub command: Option<String>,
+    /// Seconds to wait for the server to go down, before supervised servers get SIGTERM.
+    #[serde(default = "default_stop_timeout")]
+    pub timeout: u64,
+    /// Seconds to wait after SIGTERM, before SIGKILL.
+    #[serde(default = "default_kill_timeout")]
+    pub kill_timeout: u64,
+}
+
+impl Default for StopConfig {
+    fn default() -> Self {
+        StopConfig {
+            warnings: default_stop_warnings(),
+            warn_format: default_warn_format(),
+            save_command: None,
+            save_wait: default_save_wait(),
+            command: None,
+            timeout: default_stop_timeout(),
+            kill_timeout: default_kill_timeout(),
+        }
+    }
+}
+
+fn default_stop_warnings() -> Vec<u64> {
+    vec![600, 300, 60, 30, 10]
+}
+
+fn default_warn_format() -> String {
+    "say Server stops in {time}".to_string()
+}
+
+fn default_save_wait() -> u64 {
+    5
+}
+
+fn default_stop_timeout() -> u64 {
+    60
+}
+
+fn default_kill_timeout() -> u64 {
+    15
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct PermissionConfig {
+    /// Discord user ids allowed to use `/gameserver console`, in addition to admins.
+    #[serde(default)]
+    pub console: Vec<String>,
+    /// Discord user ids allowed to use `/gameserver files`, in addition to admins.
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct LimitConfig {
+    /// Address space in MiB. Virtual memory counts, so this has to be well above e.g. a java heap.
+    pub memory: Option<u64>,
+    /// CPU time in seconds. The process gets SIGXCPU once it is used up.
+    pub cpu_time: Option<u64>,
+    /// Number of open files (including sockets).
+    pub open_files: Option<u64>,
+    /// Nice level from -20 (highest priority) to 19 (lowest). Below 0 requires privileges.
+    pub nice: Option<i32>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct HealthConfig {
+    pub checks: Vec<HealthCheck>,
+    /// Seconds between two runs of the checks.
+    #[serde(default = "default_health_interval")]
+    pub interval: u64,
+    /// Seconds a single check may take.
+    #[serde(default = "default_health_timeout")]
+    pub timeout: u64,
+    /// Consecutive failures before a check counts as failing.
+    #[serde(default = "default_health_failures")]
+    pub failures: u32,
+}
+
+/// A single health check, e.g. `{"type": "tcp", "address": "127.0.0.1:25565"}`.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HealthCheck {
+    /// Connecting succeeds.
+    Tcp { address: String },
+    /// Sending `payload` (hex) gets any answer.
+    Udp { address: String, payload: String },
+    /// A GET request gets the expected status, any 2xx if `status` is missing. Only plain http.
+    Http { url: String, status: Option<u16> },
+    /// A process called `name` exists, or the process of a supervised server if `name` is missing.
+    Process { name: Option<String> },
+}
+
+fn default_health_interval() -> u64 {
+    30
+}
+
+fn default_health_timeout() -> u64 {
+    5
+}
+
+fn default_health_failures() -> u32 {
+    3
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct QueryConfig {
+    pub protocol: QueryProtocol,
+    /// Address the server answers queries on, e.g. "127.0.0.1:25565".
+    pub address: String,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryProtocol {
+    /// Minecraft server list ping.
+    Minecraft,
+    /// Source engine A2S_INFO query, also answered by e.g. Valheim.
+    A2s,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RotationConfig {
+    /// Log files to rotate besides console.log, relative to the servers folder.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// MiB a log may grow to before it is rotated.
+    #[serde(default = "default_rotation_max_size")]
+    pub max_size: u64,
+    /// Days after which a log is rotated regardless of its size.
+    #[serde(default = "default_rotation_max_age")]
+    pub max_age: u64,
+    /// Days archived logs are kept, forever if missing.
+    pub keep: Option<u64>,
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        RotationConfig {
+            files: vec![],
+            max_size: default_rotation_max_size(),
+            max_age: default_rotation_max_age(),
+            keep: None,
+        }
+    }
+}
+
+fn default_rotation_max_size() -> u64 {
+    10
+}
+
+fn default_rotation_max_age() -> u64 {
+    7
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SettingsFile {
+    /// Path relative to the servers folder, e.g. "server.properties".
+    pub file: String,
+    /// Guessed from the extension of the file if missing.
+    pub format: Option<SettingsFormat>,
+    /// Keys which may be set and the values they accept. Any key may be set if missing.
+    pub schema: Option<BTreeMap<String, SettingSchema>>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsFormat {
+    Properties,
+    Ini,
+    Json,
+}
+
+/// Values a setting accepts, e.g. `{"type": "integer", "min": 1, "max": 20}`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SettingSchema {
+    String,
+    Integer { min: Option<i64>, max: Option<i64> },
+    Boolean,
+    Enum { values: Vec<String> },
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct WakeConfig {
+    /// Address listened on while the server is stopped, usually its own, e.g. "0.0.0.0:25565".
+    pub address: String,
+    #[serde(default)]
+    pub protocol: WakeProtocol,
+    /// Shown to players where the protocol allows, "<name> is starting" if missing.
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WakeProtocol {
+    /// Any connection starts the server.
+    #[default]
+    Tcp,
+    /// Joining starts the server and is answered with the message, the server list shows it too.
+    Minecraft,
+}
@@ -0,0 +1,45 @@
+use std::process::Command;
+
+use serenity::prelude::*;
+
+use super::{config, rcon, supervisor};
+
+/// Sends a line to the console of a gameserver and returns the response, if there is one.
+///
+/// Uses rcon if configured, otherwise the stdin of a supervised server, otherwise the configured
+/// console command.
+pub async fn send(ctx: &Context, server: &str, line: &str) -> Result<String, String> {
+    // a line break would let the line run a second command
+    let line = line.replace(['\n', '\r'], " ");
+    let config = config::load(server);
+
+    if let Some(rcon) = config.console.rcon {
+        tokio::task::spawn_blocking(move || rcon::execute(&rcon.address, &rcon.password, &line))
+            .await
+            .expect("rcon task panicked")
+    } else if supervisor::pid(ctx, server).await.is_some() {
+        supervisor::write_line(ctx, server, &line).await?;
+        Ok(String::new())
+    } else if let Some(command) = config.console.command {
+        let output = tokio::task::spawn_blocking(move || {
+            // the line is passed as an argument instead of being pasted into the command
+            Command::new("bash")
+                .arg("-c")
+                .arg(command)
+                .arg("console")
+                .arg(line)
+                .output()
+        })
+        .await
+        .expect("console command task panicked")
+        .map_err(|why| format!("could not run console command: {why}"))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(format!("console command failed: {}", output.status))
+        }
+    } else {
+        Err(format!("{server} has no console configured"))
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::{Duration, Instant};
+
+use serenity::prelude::*;
+
+use super::config;
+use super::status::ServerState;
+use super::{discover, state, tracker};
+
+const READY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Depth first search adding `server` to `order` after everything it depends on.
+/// `depends_on` looks up the dependencies of a server in its config.
+fn visit(
+    server: &str,
+    known: &[String],
+    depends_on: &dyn Fn(&str) -> Vec<String>,
+    visiting: &mut Vec<String>,
+    order: &mut Vec<String>,
+) -> Result<(), String> {
+    if order.iter().any(|s| s == server) {
+        return Ok(());
+    }
+    if visiting.iter().any(|s| s == server) {
+        visiting.push(server.to_string());
+        return Err(format!("Circular dependency: {}", visiting.join(" -> ")));
+    }
+    if !known.iter().any(|s| s == server) {
+        return Err(format!(
+            "{} depends on {server}, which does not exist.",
+            visiting.last().map(String::as_str).unwrap_or(server)
+        ));
+    }
+
+    visiting.push(server.to_string());
+    for dependency in depends_on(server) {
+        visit(&dependency, known, depends_on, visiting, order)?;
+    }
+    visiting.pop();
+    order.push(server.to_string());
+    Ok(())
+}
+
+/// Everything `server` depends on, directly or through others, in the order it has to be started.
+pub fn start_order(server: &str) -> Result<Vec<String>, String> {
+    let known = discover();
+    let mut order: Vec<String> = vec![];
+    let depends_on = |server: &str| config::load(server).depends_on;
+    visit(server, &known, &depends_on, &mut vec![], &mut order)?;
+    order.pop(); // the server itself
+    Ok(order)
+}
+
+/// Servers depending on `server`, directly or through others.
+pub fn dependents(server: &str) -> Vec<String> {
+    discover()
+        .into_iter()
+        .filter(|other| other != server)
+        .filter(|other| {
+            start_order(other)
+                .map(|order| order.iter().any(|s| s == server))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Whether a dependency in `status` can be relied on. Servers without status.sh or query are
+/// Unknown, those count as running while the bot has them recorded as started.
+pub fn counts_as_running(server: &str, status: ServerState) -> bool {
+    match status {
+        ServerState::Running => true,
+        ServerState::Stopped => false,
+        ServerState::Unknown => state::get(server).running,
+    }
+}
+
+/// Dependencies of `server` which are not running.
+pub async fn missing(ctx: &Context, server: &str) -> Vec<String> {
+    let mut missing = vec![];
+    for dependency in start_order(server).unwrap_or_default() {
+        let status = tracker::probe(ctx, &dependency).await.status.state;
+        if !counts_as_running(&dependency, status) {
+            missing.push(dependency);
+        }
+    }
+    missing
+}
+
+/// Waits until a server counts as running (see status.sh and query), for up to its `ready_timeout`.
+/// Servers whose state is Unknown can't be waited for, they count as ready once started.
+pub async fn wait_until_ready(ctx: &Context, server: &str) -> Result<(), String> {
+    let timeout = Duration::from_secs(config::load(server).ready_timeout);
+    let started = Instant::now();
+    loop {
+        let status = tracker::probe(ctx, server).await.status.state;
+        if counts_as_running(server, status) {
+            return Ok(());
+        }
+        if started.elapsed() >= timeout {
+            return Err(format!(
+                "{server} was not ready after {}",
+                super::format_duration(timeout)
+            ));
+        }
+        tokio::time::sleep(READY_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Start order of `server` in a setup given as (server, its dependencies).
+    fn order_of(server: &str, setup: &[(&str, &[&str])]) -> Result<Vec<String>, String> {
+        let configs: HashMap<&str, &[&str]> = setup.iter().copied().collect();
+        let known: Vec<String> = configs.keys().map(|name| name.to_string()).collect();
+        let depends_on = |name: &str| configs[name].iter().map(|s| s.to_string()).collect();
+        let mut order = vec![];
+        visit(server, &known, &depends_on, &mut vec![], &mut order)?;
+        Ok(order)
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        let setup: &[(&str, &[&str])] = &[
+            ("survival", &["proxy", "database"]),
+            ("proxy", &["database"]),
+            ("database", &[]),
+            ("creative", &["proxy"]),
+        ];
+        assert_eq!(
+            order_of("survival", setup).unwrap(),
+            vec!["database", "proxy", "survival"]
+        );
+        assert_eq!(order_of("database", setup).unwrap(), vec!["database"]);
+    }
+
+    #[test]
+    fn circular_dependencies_are_refused() {
+        let setup: &[(&str, &[&str])] = &[("a", &["b"]), ("b", &["c"]), ("c", &["a"])];
+        assert_eq!(
+            order_of("a", setup),
+            Err("Circular dependency: a -> b -> c -> a".to_string())
+        );
+        let setup: &[(&str, &[&str])] = &[("a", &["a"])];
+        assert_eq!(
+            order_of("a", setup),
+            Err("Circular dependency: a -> a".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_dependencies_are_refused() {
+        let setup: &[(&str, &[&str])] = &[("survival", &["proxy"]), ("proxy", &["velocity"])];
+        assert_eq!(
+            order_of("survival", setup),
+            Err("proxy depends on velocity, which does not exist.".to_string())
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serenity::prelude::*;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+
+use super::config::{self, HealthCheck, HealthConfig};
+use super::status::ServerState;
+use super::{state, supervisor, tracker};
+use crate::notifications;
+
+/// How often the background task looks for checks which are due.
+const TICK: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthState {
+    /// All checks pass.
+    Healthy,
+    /// Some checks fail.
+    Degraded,
+    /// All checks fail.
+    Down,
+}
+
+impl fmt::Display for HealthState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthState::Healthy => write!(f, "🟢 healthy"),
+            HealthState::Degraded => write!(f, "🟡 degraded"),
+            HealthState::Down => write!(f, "🔴 down"),
+        }
+    }
+}
+
+/// Result of the health checks of a running server.
+#[derive(Clone, Debug)]
+pub struct ServerHealth {
+    pub state: HealthState,
+    /// Failing checks and why they fail.
+    pub failing: Vec<String>,
+}
+
+impl ServerHealth {
+    /// State followed by the failing checks, one per line.
+    pub fn describe(&self) -> String {
+        let mut description = self.state.to_string();
+        for failing in &self.failing {
+            description.push_str(&format!("\n{failing}"));
+        }
+        description
+    }
+}
+
+/// Health of the running servers with health checks, by folder name.
+pub struct Health;
+
+impl TypeMapKey for Health {
+    type Value = Arc<RwLock<HashMap<String, ServerHealth>>>;
+}
+
+async fn health(ctx: &Context) -> Arc<RwLock<HashMap<String, ServerHealth>>> {
+    let data = ctx.data.read().await;
+    data.get::<Health>()
+        .expect("Health should be in the client data")
+        .clone()
+}
+
+/// Health of a server, None if it has no checks, isn't running or wasn't checked yet.
+pub async fn get(ctx: &Context, server: &str) -> Option<ServerHealth> {
+    health(ctx).await.read().await.get(server).cloned()
+}
+
+impl fmt::Display for HealthCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthCheck::Tcp { address } => write!(f, "tcp {address}"),
+            HealthCheck::Udp { address, .. } => write!(f, "udp {address}"),
+            HealthCheck::Http { url, .. } => write!(f, "http {url}"),
+            HealthCheck::Process { name: Some(name) } => write!(f, "process {name}"),
+            HealthCheck::Process { name: None } => write!(f, "process"),
+        }
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("payload is not hex".to_string());
+    }
+    if !hex.len().is_multiple_of(2) {
+        return Err("payload has an odd number of hex digits".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|why| why.to_string()))
+        .collect()
+}
+
+async fn check_udp(address: &str, payload: &str) -> Result<(), String> {
+    let payload = decode_hex(payload)?;
+    let target = lookup_host(address)
+        .await
+        .map_err(|why| why.to_string())?
+        .next()
+        .ok_or(format!("could not resolve {address}"))?;
+    let local = if target.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+
+    let socket = UdpSocket::bind(local)
+        .await
+        .map_err(|why| why.to_string())?;
+    socket
+        .send_to(&payload, target)
+        .await
+        .map_err(|why| why.to_string())?;
+    let mut buffer = [0; 1500];
+    socket
+        .recv_from(&mut buffer)
+        .await
+        .map_err(|why| why.to_string())?;
+    Ok(())
+}
+
+async fn check_http(url: &str, expected: Option<u16>) -> Result<(), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or("only http:// urls are supported")?;
+    let (host, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{host}:80")
+    };
+
+    let mut stream = TcpStream::connect(&address)
+        .await
+        .map_err(|why| why.to_string())?;
+    let request = format!("GET {path} HTTP/1.0\r\nHost: {host}\r\nConnection: close\r\n\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|why| why.to_string())?;
+
+    // e.g. "HTTP/1.1 200 OK"
+    let mut status_line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut status_line)
+        .await
+        .map_err(|why| why.to_string())?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or(format!("invalid response: {}", status_line.trim()))?;
+
+    let ok = match expected {
+        Some(expected) => status == expected,
+        None => (200..300).contains(&status),
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(format!("status {status}"))
+    }
+}
+
+/// Whether a process with this name (as in /proc/<pid>/comm) exists.
+fn process_named(name: &str) -> bool {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        fs::read_to_string(entry.path().join("comm"))
+            .map(|comm| comm.trim() == name)
+            .unwrap_or(false)
+    })
+}
+
+async fn check_process(ctx: &Context, server: &str, name: &Option<String>) -> Result<(), String> {
+    let alive = match name {
+        Some(name) => process_named(name),
+        None => match supervisor::pid(ctx, server).await {
+            Some(_) => true,
+            None => state::get(server)
+                .pid
+                .map(state::pid_alive)
+                .unwrap_or(false),
+        },
+    };
+    if alive {
+        Ok(())
+    } else {
+        Err("not running".to_string())
+    }
+}
+
+async fn run_check(
+    ctx: &Context,
+    server: &str,
+    check: &HealthCheck,
+    timeout: Duration,
+) -> Result<(), String> {
+    let result = tokio::time::timeout(timeout, async {
+        match check {
+            HealthCheck::Tcp { address } => TcpStream::connect(address)
+                .await
+                .map(|_| ())
+                .map_err(|why| why.to_string()),
+            HealthCheck::Udp { address, payload } => check_udp(address, payload).await,
+            HealthCheck::Http { url, status } => check_http(url, *status).await,
+            HealthCheck::Process { name } => check_process(ctx, server, name).await,
+        }
+    })
+    .await;
+
+    match result {
+        Ok(result) => result,
+        Err(_) => Err(format!("timed out after {}s", timeout.as_secs())),
+    }
+}
+
+/// Check state of one running server.
+struct Watched {
+    config: HealthConfig,
+    /// Consecutive failures and the last error, per check.
+    failures: Vec<(u32, String)>,
+    last_run: Option<Instant>,
+    state: Option<HealthState>,
+}
+
+impl Watched {
+    fn new(config: HealthConfig) -> Self {
+        Watched {
+            failures: vec![(0, String::new()); config.checks.len()],
+            config,
+            last_run: None,
+            state: None,
+        }
+    }
+
+    fn due(&self) -> bool {
+        self.last_run
+            .map(|last| last.elapsed() >= Duration::from_secs(self.config.interval))
+            .unwrap_or(true)
+    }
+
+    async fn run(&mut self, ctx: &Context, server: &str) -> ServerHealth {
+        self.last_run = Some(Instant::now());
+        let timeout = Duration::from_secs(self.config.timeout);
+
+        let mut failing: Vec<String> = vec![];
+        for (check, (failures, error)) in self.config.checks.iter().zip(&mut self.failures) {
+            match run_check(ctx, server, check, timeout).await {
+                Ok(()) => *failures = 0,
+                Err(why) => {
+                    *failures += 1;
+                    *error = why;
+                }
+            }
+            if *failures >= self.config.failures {
+                failing.push(format!("{check}: {error}"));
+            }
+        }
+
+        let state = match failing.len() {
+            0 => HealthState::Healthy,
+            n if n == self.config.checks.len() => HealthState::Down,
+            _ => HealthState::Degraded,
+        };
+        ServerHealth { state, failing }
+    }
+}
+
+/// Runs the health checks of running servers and alerts the notification channel when their
+/// health changes.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut watched: HashMap<String, Watched> = HashMap::new();
+        let mut interval = tokio::time::interval(TICK);
+
+        loop {
+            interval.tick().await;
+
+            for tracked in tracker::snapshot(&ctx).await {
+                let server = tracked.server;
+                let health_config = match (tracked.status.state, config::load(&server).health) {
+                    (ServerState::Running, Some(health)) if !health.checks.is_empty() => health,
+                    _ => {
+                        // nothing to check, stopped servers are not unhealthy
+                        watched.remove(&server);
+                        health(&ctx).await.write().await.remove(&server);
+                        continue;
+                    }
+                };
+
+                // (re)start checking if the server is new or its checks were changed
+                if watched
+                    .get(&server)
+                    .map(|w| w.config != health_config)
+                    .unwrap_or(true)
+                {
+                    watched.insert(server.clone(), Watched::new(health_config));
+                }
+
+                let checked = watched.get_mut(&server).expect("server was just inserted");
+                if !checked.due() {
+                    continue;
+                }
+                let result = checked.run(&ctx, &server).await;
+
+                let previous = checked.state.replace(result.state);
+                if previous.is_some_and(|previous| previous != result.state)
+                    || (previous.is_none() && result.state != HealthState::Healthy)
+                {
+                    let mut alert = format!("**{}** is {}", tracked.config.name, result.state);
+                    for failing in &result.failing {
+                        alert.push_str(&format!("\n* {failing}"));
+                    }
+                    notifications::send(&ctx, &alert).await;
+                }
+
+                health(&ctx).await.write().await.insert(server, result);
+            }
+        }
+    });
+}
@@ -0,0 +1,101 @@
+use std::io;
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use super::config::LimitConfig;
+use super::format_duration;
+
+/// Seconds between SIGXCPU and SIGKILL, so servers may save before the CPU time limit kills them.
+const CPU_TIME_GRACE: u64 = 10;
+
+fn set_limit(resource: libc::__rlimit_resource_t, soft: u64, hard: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: soft as libc::rlim_t,
+        rlim_max: hard as libc::rlim_t,
+    };
+    if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Applies the limits to the current process. Meant to run between fork and exec (`pre_exec`), so
+/// it only calls async-signal-safe functions. The limits are inherited by everything start.sh runs.
+pub fn apply(limits: &LimitConfig) -> io::Result<()> {
+    if let Some(memory) = limits.memory {
+        let bytes = memory.saturating_mul(1024 * 1024);
+        set_limit(libc::RLIMIT_AS, bytes, bytes)?;
+    }
+    if let Some(cpu_time) = limits.cpu_time {
+        set_limit(libc::RLIMIT_CPU, cpu_time, cpu_time + CPU_TIME_GRACE)?;
+    }
+    if let Some(open_files) = limits.open_files {
+        set_limit(libc::RLIMIT_NOFILE, open_files, open_files)?;
+    }
+    if let Some(nice) = limits.nice {
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Name of a signal the server may have died from.
+fn signal_name(signal: i32) -> String {
+    match signal {
+        libc::SIGKILL => "SIGKILL".to_string(),
+        libc::SIGTERM => "SIGTERM".to_string(),
+        libc::SIGXCPU => "SIGXCPU".to_string(),
+        libc::SIGSEGV => "SIGSEGV".to_string(),
+        libc::SIGABRT => "SIGABRT".to_string(),
+        libc::SIGBUS => "SIGBUS".to_string(),
+        _ => format!("signal {signal}"),
+    }
+}
+
+/// How the process exited, e.g. "was killed by SIGKILL".
+pub fn describe_exit(status: &ExitStatus) -> String {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => format!("exited with code {code}"),
+        (None, Some(signal)) => format!("was killed by {}", signal_name(signal)),
+        (None, None) => format!("exited: {status}"),
+    }
+}
+
+/// The limit most likely responsible for the exit, judged by the signal and the last output lines.
+pub fn exceeded(status: &ExitStatus, output: &[String], limits: &LimitConfig) -> Option<String> {
+    let output_contains = |patterns: &[&str]| {
+        output
+            .iter()
+            .any(|line| patterns.iter().any(|pattern| line.contains(pattern)))
+    };
+
+    if let Some(cpu_time) = limits.cpu_time {
+        // SIGKILL follows CPU_TIME_GRACE seconds after SIGXCPU
+        if matches!(status.signal(), Some(libc::SIGXCPU) | Some(libc::SIGKILL)) {
+            return Some(format!(
+                "It probably used up its CPU time limit of {}.",
+                format_duration(Duration::from_secs(cpu_time))
+            ));
+        }
+    }
+    if let Some(memory) = limits.memory {
+        if output_contains(&["OutOfMemoryError", "Cannot allocate memory", "bad_alloc"])
+            || matches!(status.signal(), Some(libc::SIGSEGV) | Some(libc::SIGABRT))
+        {
+            return Some(format!(
+                "It probably ran out of memory, its limit is {memory} MiB."
+            ));
+        }
+    }
+    if let Some(open_files) = limits.open_files {
+        if output_contains(&["Too many open files"]) {
+            return Some(format!(
+                "It ran out of file handles, its limit is {open_files} open files."
+            ));
+        }
+    }
+    None
+}
@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Follows a growing log file, similar to `tail -f`.
+#[derive(Default)]
+pub struct LogTail {
+    /// Read position, None until the file was seen for the first time.
+    offset: Option<u64>,
+    /// Incomplete last line, kept until the rest of it is written.
+    partial: String,
+}
+
+impl LogTail {
+    /// Returns the lines written since the last call.
+    /// The first call only remembers the end of the file, so old lines are not replayed.
+    pub fn read_new_lines(&mut self, path: &str) -> Vec<String> {
+        let Ok(mut file) = File::open(path) else {
+            return vec![];
+        };
+        let Ok(len) = file.metadata().map(|m| m.len()) else {
+            return vec![];
+        };
+
+        let offset = match self.offset {
+            None => {
+                self.offset = Some(len);
+                return vec![];
+            }
+            // file was truncated or rotated, start over
+            Some(offset) if offset > len => {
+                self.partial.clear();
+                0
+            }
+            Some(offset) => offset,
+        };
+
+        if file.seek(SeekFrom::Start(offset)).is_err() {
+            return vec![];
+        }
+        let mut buffer = Vec::new();
+        if let Err(why) = file.read_to_end(&mut buffer) {
+            println!("could not read {path}: {why}");
+            return vec![];
+        }
+        self.offset = Some(offset + buffer.len() as u64);
+
+        self.partial.push_str(&String::from_utf8_lossy(&buffer));
+        let mut lines: Vec<String> = self
+            .partial
+            .split('\n')
+            .map(|l| l.trim_end_matches('\r').to_string())
+            .collect();
+        // the last element is either empty or an unfinished line
+        self.partial = lines.pop().unwrap_or_default();
+
+        lines
+    }
+}
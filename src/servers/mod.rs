@@ -0,0 +1,153 @@
+pub mod config;
+pub mod console;
+pub mod dependencies;
+pub mod health;
+pub mod limits;
+pub mod log;
+pub mod presets;
+pub mod query;
+pub mod rcon;
+pub mod rotation;
+pub mod settings;
+pub mod state;
+pub mod status;
+pub mod supervisor;
+pub mod tracker;
+pub mod usage;
+pub mod versions;
+
+use std::fs;
+use std::time::Duration;
+
+/// Directory containing one folder (with control scripts and config.json) per gameserver.
+pub const GAMESERVER_DIR: &str = "./gameservers/";
+
+/// Returns the folder names of all gameservers, sorted alphabetically.
+pub fn discover() -> Vec<String> {
+    let mut servers: Vec<String> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(GAMESERVER_DIR) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                servers.push(entry.file_name().display().to_string());
+            }
+        }
+    } else {
+        println!("could not read {GAMESERVER_DIR}");
+    }
+
+    servers.sort();
+    servers
+}
+
+/// Whether there is a gameserver with the given folder name. Names given by users have to be
+/// checked using this before use, as they end up in paths.
+pub fn exists(server: &str) -> bool {
+    discover().iter().any(|s| s == server)
+}
+
+/// Path of a file inside the folder of the given gameserver.
+pub fn path(server: &str, file: &str) -> String {
+    format!("{GAMESERVER_DIR}{server}/{file}")
+}
+
+/// Writes a file next to `target` and renames it over it, so the server never sees a half written
+/// file.
+pub fn write_atomically(target: &str, content: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let temporary = format!("{target}.new");
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, target)
+}
+
+/// Formats a duration like "1h 5m 3s", leaving out leading zero units.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// Days since 1970-01-01 of a date, see http://howardhinnant.github.io/date_algorithms.html
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Year, month and day of a unix timestamp (UTC).
+pub fn date(timestamp: u64) -> (i64, u32, u32) {
+    let days = (timestamp / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Formats a unix timestamp as a date like "2026-10-19" (UTC).
+pub fn format_date(timestamp: u64) -> String {
+    let (year, month, day) = date(timestamp);
+    format!("{year}-{month:02}-{day:02}")
+}
+
+/// Formats a unix timestamp for file names, like "2026-10-19.120000" (UTC).
+pub fn format_file_timestamp(timestamp: u64) -> String {
+    format!(
+        "{}.{:02}{:02}{:02}",
+        format_date(timestamp),
+        timestamp / 3600 % 24,
+        timestamp / 60 % 60,
+        timestamp % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_from_civil_counts_from_epoch() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(2026, 10, 19), 1792368000 / 86400);
+    }
+
+    #[test]
+    fn date_inverts_days_from_civil() {
+        assert_eq!(date(0), (1970, 1, 1));
+        assert_eq!(date(951782400), (2000, 2, 29));
+        for (year, month, day) in [(1999, 12, 31), (2024, 2, 29), (2026, 10, 19), (2100, 3, 1)] {
+            let timestamp = (days_from_civil(year, month, day) * 86400) as u64;
+            assert_eq!(date(timestamp), (year, month, day));
+            assert_eq!(date(timestamp + 86399), (year, month, day));
+        }
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_date(1792411205), "2026-10-19");
+        assert_eq!(format_file_timestamp(1792411205), "2026-10-19.120005");
+        assert_eq!(format_file_timestamp(0), "1970-01-01.000000");
+    }
+}
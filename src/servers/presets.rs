@@ -0,0 +1,131 @@
+use serde_json::{json, Value};
+
+/// Defaults for a game, selected by the `type` field of config.json. Fields set in config.json
+/// override them, objects are merged field by field.
+pub fn preset(game_type: &str) -> Option<Value> {
+    let preset = match game_type {
+        "minecraft" => json!({
+            "supervised": true,
+            "query": { "protocol": "minecraft", "address": "127.0.0.1:25565" },
+            "log": {
+                "file": "logs/latest.log",
+                "join_pattern": "\\]: (?P<player>\\w+) joined the game",
+                "leave_pattern": "\\]: (?P<player>\\w+) left the game"
+            },
+            "stop": {
+                "warn_format": "say Server stops in {time}",
+                "save_command": "save-all",
+                "command": "stop"
+            }
+        }),
+        "valheim" => json!({
+            "supervised": true,
+            // the query port is the game port + 1
+            "query": { "protocol": "a2s", "address": "127.0.0.1:2457" },
+            // no log: the only line naming a player (ZDOID) is also logged on every death and
+            // respawn, so it can't tell joins apart
+            // valheim has no console, it saves and exits on SIGTERM
+            "stop": { "timeout": 5, "kill_timeout": 60 }
+        }),
+        "factorio" => json!({
+            "supervised": true,
+            "log": {
+                "file": "console.log",
+                "join_pattern": "\\[JOIN\\] (?P<player>\\S+) joined the game",
+                "leave_pattern": "\\[LEAVE\\] (?P<player>\\S+) left the game"
+            },
+            "stop": {
+                "warn_format": "Server stops in {time}",
+                "save_command": "/server-save",
+                "command": "/quit"
+            }
+        }),
+        "terraria" => json!({
+            "supervised": true,
+            "log": {
+                "file": "console.log",
+                "join_pattern": "^(?P<player>.+) has joined\\.",
+                "leave_pattern": "^(?P<player>.+) has left\\."
+            },
+            "stop": {
+                "warn_format": "say Server stops in {time}",
+                "save_command": "save",
+                "command": "exit"
+            }
+        }),
+        "source" => json!({
+            "supervised": true,
+            "query": { "protocol": "a2s", "address": "127.0.0.1:27015" },
+            "log": {
+                "file": "console.log",
+                "join_pattern": "\"(?P<player>.+)<\\d+><[^>]*><[^>]*>\" entered the game",
+                "leave_pattern": "\"(?P<player>.+)<\\d+><[^>]*><[^>]*>\" disconnected"
+            },
+            "stop": {
+                "warn_format": "say Server stops in {time}",
+                "command": "quit"
+            }
+        }),
+        _ => return None,
+    };
+    Some(preset)
+}
+
+/// Merges `overrides` into `base`. Objects are merged recursively, anything else is replaced.
+pub fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_objects_field_by_field() {
+        let mut base = preset("minecraft").unwrap();
+        merge(
+            &mut base,
+            json!({
+                "query": { "address": "127.0.0.1:25575" },
+                "stop": { "command": "end" },
+                "name": "Minecraft"
+            }),
+        );
+        assert_eq!(base["query"]["protocol"], "minecraft");
+        assert_eq!(base["query"]["address"], "127.0.0.1:25575");
+        assert_eq!(base["stop"]["save_command"], "save-all");
+        assert_eq!(base["stop"]["command"], "end");
+        assert_eq!(base["name"], "Minecraft");
+        assert_eq!(base["supervised"], true);
+    }
+
+    #[test]
+    fn merge_replaces_everything_else() {
+        let mut base = json!({ "supervised": true, "log": { "file": "a.log" }, "list": [1, 2] });
+        merge(
+            &mut base,
+            json!({ "supervised": false, "log": null, "list": [3] }),
+        );
+        assert_eq!(
+            base,
+            json!({ "supervised": false, "log": null, "list": [3] })
+        );
+    }
+
+    #[test]
+    fn unknown_type_has_no_preset() {
+        assert_eq!(preset("tetris"), None);
+    }
+}
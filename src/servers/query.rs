@@ -0,0 +1,211 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::config::{QueryConfig, QueryProtocol};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a server answered to a status query.
+#[derive(Default, Debug)]
+pub struct QueryInfo {
+    pub players: Option<u32>,
+    pub max_players: Option<u32>,
+    pub map: Option<String>,
+    pub version: Option<String>,
+}
+
+fn resolve(address: &str) -> Result<SocketAddr, String> {
+    address
+        .to_socket_addrs()
+        .map_err(|why| format!("invalid query address {address}: {why}"))?
+        .next()
+        .ok_or(format!("invalid query address {address}"))
+}
+
+/// Asks the server for its status. Blocks for up to a few seconds, so call this from a blocking
+/// context.
+pub fn query(config: &QueryConfig) -> Result<QueryInfo, String> {
+    let address = resolve(&config.address)?;
+    match config.protocol {
+        QueryProtocol::Minecraft => minecraft(address),
+        QueryProtocol::A2s => a2s(address),
+    }
+}
+
+// minecraft server list ping, see https://minecraft.wiki/w/Java_Edition_protocol/Server_List_Ping
+
+pub fn write_varint(buffer: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7f == 0 {
+            buffer.push(value as u8);
+            return;
+        }
+        buffer.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+pub fn read_varint(stream: &mut impl Read) -> Result<i32, String> {
+    let mut value: u32 = 0;
+    for position in 0..5 {
+        let mut byte = [0u8; 1];
+        stream
+            .read_exact(&mut byte)
+            .map_err(|why| format!("could not read packet: {why}"))?;
+        value |= ((byte[0] & 0x7f) as u32) << (7 * position);
+        if byte[0] & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err("invalid varint in packet".to_string())
+}
+
+/// Prefixes a packet with its length.
+pub fn packet(body: Vec<u8>) -> Vec<u8> {
+    let mut packet: Vec<u8> = vec![];
+    write_varint(&mut packet, body.len() as i32);
+    packet.extend(body);
+    packet
+}
+
+fn minecraft(address: SocketAddr) -> Result<QueryInfo, String> {
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)
+        .map_err(|why| format!("could not connect to {address}: {why}"))?;
+    stream.set_read_timeout(Some(TIMEOUT)).ok();
+    stream.set_write_timeout(Some(TIMEOUT)).ok();
+
+    let host = address.ip().to_string();
+    let mut handshake: Vec<u8> = vec![];
+    write_varint(&mut handshake, 0x00); // packet id
+    write_varint(&mut handshake, -1); // protocol version, -1 when pinging
+    write_varint(&mut handshake, host.len() as i32);
+    handshake.extend(host.as_bytes());
+    handshake.extend(address.port().to_be_bytes());
+    write_varint(&mut handshake, 1); // next state: status
+
+    let mut request = packet(handshake);
+    request.extend(packet(vec![0x00])); // status request
+    stream
+        .write_all(&request)
+        .map_err(|why| format!("could not send query: {why}"))?;
+
+    let _length = read_varint(&mut stream)?;
+    let _id = read_varint(&mut stream)?;
+    let json_length = read_varint(&mut stream)?;
+    if !(0..=1 << 20).contains(&json_length) {
+        return Err(format!("invalid query response length {json_length}"));
+    }
+    let mut json = vec![0u8; json_length as usize];
+    stream
+        .read_exact(&mut json)
+        .map_err(|why| format!("could not read query response: {why}"))?;
+
+    let json: Value =
+        serde_json::from_slice(&json).map_err(|why| format!("invalid query response: {why}"))?;
+    let count = |value: &Value| value.as_u64().map(|v| v as u32);
+    Ok(QueryInfo {
+        players: count(&json["players"]["online"]),
+        max_players: count(&json["players"]["max"]),
+        map: None,
+        version: json["version"]["name"].as_str().map(str::to_string),
+    })
+}
+
+// source A2S_INFO, see https://developer.valvesoftware.com/wiki/Server_queries#A2S_INFO
+
+const A2S_INFO: &[u8] = b"\xff\xff\xff\xffTSource Engine Query\0";
+const S2C_CHALLENGE: u8 = 0x41;
+const S2A_INFO: u8 = 0x49;
+
+/// Reads the fields of an A2S_INFO response one after another.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let (&byte, rest) = self.data.split_first().ok_or("query response too short")?;
+        self.data = rest;
+        Ok(byte)
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), String> {
+        if self.data.len() < count {
+            return Err("query response too short".to_string());
+        }
+        self.data = &self.data[count..];
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let end = self
+            .data
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("unterminated string in query response")?;
+        let string = String::from_utf8_lossy(&self.data[..end]).to_string();
+        self.data = &self.data[end + 1..];
+        Ok(string)
+    }
+}
+
+fn a2s(address: SocketAddr) -> Result<QueryInfo, String> {
+    let local = if address.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(local).map_err(|why| format!("could not bind socket: {why}"))?;
+    // connected, so a stopped server is reported right away (ICMP port unreachable) instead of
+    // running into the timeout
+    socket
+        .connect(address)
+        .map_err(|why| format!("could not connect to {address}: {why}"))?;
+    socket.set_read_timeout(Some(TIMEOUT)).ok();
+
+    let mut request = A2S_INFO.to_vec();
+    let mut buffer = [0u8; 1400];
+    let response = loop {
+        socket
+            .send(&request)
+            .map_err(|why| format!("could not send query: {why}"))?;
+        let length = socket
+            .recv(&mut buffer)
+            .map_err(|why| format!("no answer from {address}: {why}"))?;
+        let response = &buffer[..length];
+
+        // servers may ask to repeat the request with a challenge, to prevent spoofing
+        if response.len() >= 9 && response[4] == S2C_CHALLENGE && request.len() == A2S_INFO.len() {
+            request.extend(&response[5..9]);
+            continue;
+        }
+        break response;
+    };
+
+    let mut reader = Reader { data: response };
+    reader.skip(4)?; // -1 header of single packet responses
+    if reader.byte()? != S2A_INFO {
+        return Err("unexpected query response".to_string());
+    }
+    reader.byte()?; // protocol
+    let _name = reader.string()?;
+    let map = reader.string()?;
+    let _folder = reader.string()?;
+    let _game = reader.string()?;
+    reader.skip(2)?; // steam app id
+    let players = reader.byte()?;
+    let max_players = reader.byte()?;
+    reader.skip(5)?; // bots, server type, environment, visibility, vac
+    let version = reader.string()?;
+
+    Ok(QueryInfo {
+        players: Some(players as u32),
+        max_players: Some(max_players as u32),
+        map: Some(map).filter(|map| !map.is_empty()),
+        version: Some(version).filter(|version| !version.is_empty()),
+    })
+}
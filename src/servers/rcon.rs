@@ -0,0 +1,83 @@
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+// packet types of the source rcon protocol, used by most games offering rcon.
+// https://developer.valvesoftware.com/wiki/Source_RCON_Protocol
+const SERVERDATA_AUTH: i32 = 3;
+const SERVERDATA_EXECCOMMAND: i32 = 2;
+const SERVERDATA_AUTH_RESPONSE: i32 = 2;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn write_packet(stream: &mut TcpStream, id: i32, kind: i32, body: &str) -> Result<(), String> {
+    let mut packet: Vec<u8> = vec![];
+    // size does not include the size field itself
+    packet.extend(((body.len() + 10) as i32).to_le_bytes());
+    packet.extend(id.to_le_bytes());
+    packet.extend(kind.to_le_bytes());
+    packet.extend(body.as_bytes());
+    packet.extend([0, 0]);
+
+    stream
+        .write_all(&packet)
+        .map_err(|why| format!("could not send rcon packet: {why}"))
+}
+
+fn read_i32(stream: &mut TcpStream) -> Result<i32, String> {
+    let mut buffer = [0u8; 4];
+    stream
+        .read_exact(&mut buffer)
+        .map_err(|why| format!("could not read rcon packet: {why}"))?;
+    Ok(i32::from_le_bytes(buffer))
+}
+
+/// Reads one packet, returning its id, type and body.
+fn read_packet(stream: &mut TcpStream) -> Result<(i32, i32, String), String> {
+    let size = read_i32(stream)?;
+    if !(10..=4096 + 10).contains(&size) {
+        return Err(format!("invalid rcon packet size {size}"));
+    }
+    let id = read_i32(stream)?;
+    let kind = read_i32(stream)?;
+
+    let mut body = vec![0u8; size as usize - 8];
+    stream
+        .read_exact(&mut body)
+        .map_err(|why| format!("could not read rcon packet: {why}"))?;
+    body.truncate(body.len() - 2); // trailing null bytes
+
+    Ok((id, kind, String::from_utf8_lossy(&body).to_string()))
+}
+
+/// Runs a single command over rcon and returns the servers response.
+/// Blocks for up to a few seconds, so call this from a blocking context.
+pub fn execute(address: &str, password: &str, command: &str) -> Result<String, String> {
+    let socket_address = address
+        .to_socket_addrs()
+        .map_err(|why| format!("invalid rcon address {address}: {why}"))?
+        .next()
+        .ok_or(format!("invalid rcon address {address}"))?;
+
+    let mut stream = TcpStream::connect_timeout(&socket_address, TIMEOUT)
+        .map_err(|why| format!("could not connect to rcon at {address}: {why}"))?;
+    stream.set_read_timeout(Some(TIMEOUT)).ok();
+    stream.set_write_timeout(Some(TIMEOUT)).ok();
+
+    write_packet(&mut stream, 1, SERVERDATA_AUTH, password)?;
+    // some servers send an empty response value before the auth response
+    loop {
+        let (id, kind, _) = read_packet(&mut stream)?;
+        if kind == SERVERDATA_AUTH_RESPONSE {
+            if id == -1 {
+                return Err("rcon authentication failed".to_string());
+            }
+            break;
+        }
+    }
+
+    write_packet(&mut stream, 2, SERVERDATA_EXECCOMMAND, command)?;
+    let (_, _, response) = read_packet(&mut stream)?;
+
+    Ok(response)
+}
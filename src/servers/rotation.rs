@@ -0,0 +1,156 @@
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use super::config::{self, RotationConfig};
+use super::supervisor::CONSOLE_LOG;
+use super::{format_file_timestamp, path};
+
+/// Folder inside the servers folder rotated logs are archived in.
+pub const ARCHIVE_DIR: &str = "archive";
+
+const MIB: u64 = 1024 * 1024;
+const DAY: u64 = 24 * 60 * 60;
+
+fn timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Start of the archive names of a log, e.g. "logs_latest.log." for logs/latest.log.
+fn archive_prefix(file: &str) -> String {
+    format!("{}.", file.replace('/', "_"))
+}
+
+/// Names of the archived logs of a server, oldest first.
+pub fn archives(server: &str) -> Vec<String> {
+    let mut archives: Vec<(SystemTime, String)> = match fs::read_dir(path(server, ARCHIVE_DIR)) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, entry.file_name().to_string_lossy().to_string()))
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+    archives.sort();
+    archives.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Archived logs of a server rotated on a date like "2026-10-19", oldest first.
+pub fn archived_on(server: &str, date: &str) -> Vec<String> {
+    archives(server)
+        .into_iter()
+        .filter(|name| name.contains(&format!(".{date}.")))
+        .collect()
+}
+
+/// When a log was last rotated, or created if it never was.
+fn last_rotation(server: &str, file: &str, log_path: &str) -> Option<u64> {
+    let prefix = archive_prefix(file);
+    let archived = archives(server)
+        .into_iter()
+        .rfind(|name| name.starts_with(&prefix))
+        .and_then(|name| fs::metadata(path(server, &format!("{ARCHIVE_DIR}/{name}"))).ok())
+        .and_then(|metadata| metadata.modified().ok());
+
+    // creation time is not supported by every file system
+    archived
+        .or_else(|| fs::metadata(log_path).and_then(|m| m.created()).ok())
+        .map(timestamp)
+}
+
+/// Compresses the log into the archive and empties it. The log is truncated instead of moved, so
+/// processes which keep it open carry on writing to it.
+fn rotate(server: &str, file: &str, log_path: &str) -> io::Result<String> {
+    fs::create_dir_all(path(server, ARCHIVE_DIR))?;
+
+    // the time of day keeps archives of the same day apart
+    let name = format!(
+        "{}{}.gz",
+        archive_prefix(file),
+        format_file_timestamp(timestamp(SystemTime::now()))
+    );
+
+    let mut encoder = GzEncoder::new(
+        File::create(path(server, &format!("{ARCHIVE_DIR}/{name}")))?,
+        Compression::default(),
+    );
+    io::copy(&mut File::open(log_path)?, &mut encoder)?;
+    encoder.finish()?;
+
+    OpenOptions::new().write(true).open(log_path)?.set_len(0)?;
+    Ok(name)
+}
+
+/// Deletes archived logs older than `keep` days.
+fn prune(server: &str, keep: u64) {
+    let max_age = Duration::from_secs(keep * DAY);
+    for name in archives(server) {
+        let archive = path(server, &format!("{ARCHIVE_DIR}/{name}"));
+        let expired = fs::metadata(&archive)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map(|age| age > max_age)
+            .unwrap_or(false);
+        if expired {
+            if let Err(why) = fs::remove_file(&archive) {
+                println!("could not delete {archive}: {why}");
+            }
+        }
+    }
+}
+
+/// Rotates a log if it is too big or too old.
+fn rotate_if_needed(server: &str, file: &str, rotation: &RotationConfig) {
+    let log_path = path(server, file);
+    let Ok(metadata) = fs::metadata(&log_path) else {
+        return;
+    };
+    if metadata.len() == 0 {
+        return;
+    }
+
+    let too_big = metadata.len() >= rotation.max_size * MIB;
+    let now = timestamp(SystemTime::now());
+    let too_old = last_rotation(server, file, &log_path)
+        .map(|last| now.saturating_sub(last) >= rotation.max_age * DAY)
+        .unwrap_or(false);
+    if !too_big && !too_old {
+        return;
+    }
+
+    match rotate(server, file, &log_path) {
+        Ok(name) => println!("rotated {log_path} into {name}"),
+        Err(why) => println!("could not rotate {log_path}: {why}"),
+    }
+}
+
+/// Rotates the logs of all gameservers as needed and deletes expired archives.
+/// Blocks while compressing, so call this from a blocking context.
+pub fn rotate_all() {
+    for server in super::discover() {
+        let rotation = config::load(&server).rotation;
+
+        let mut files = vec![CONSOLE_LOG.to_string()];
+        files.extend(rotation.files.iter().cloned());
+        for file in files {
+            if Path::new(&file).is_absolute() || file.contains("..") {
+                println!("not rotating {file} of {server}, it is outside the servers folder");
+                continue;
+            }
+            rotate_if_needed(&server, &file, &rotation);
+        }
+
+        if let Some(keep) = rotation.keep {
+            prune(&server, keep);
+        }
+    }
+}
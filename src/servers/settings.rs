@@ -0,0 +1,507 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::config::{SettingSchema, SettingsFile, SettingsFormat};
+use super::{path, write_atomically};
+
+fn format_of(settings: &SettingsFile) -> Result<SettingsFormat, String> {
+    if let Some(format) = settings.format {
+        return Ok(format);
+    }
+    match Path::new(&settings.file)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("properties") => Ok(SettingsFormat::Properties),
+        Some("ini") | Some("cfg") => Ok(SettingsFormat::Ini),
+        Some("json") => Ok(SettingsFormat::Json),
+        _ => Err(format!(
+            "The format of {} is unknown, set `format` in config.json.",
+            settings.file
+        )),
+    }
+}
+
+/// Checks a value against the schema of its key. Without a schema, anything goes.
+fn validate(settings: &SettingsFile, key: &str, value: &str) -> Result<(), String> {
+    let Some(schema) = &settings.schema else {
+        return Ok(());
+    };
+    let Some(setting) = schema.get(key) else {
+        return Err(format!(
+            "{key} can't be set. Settings which can: {}",
+            schema.keys().cloned().collect::<Vec<String>>().join(", ")
+        ));
+    };
+
+    match setting {
+        SettingSchema::String => Ok(()),
+        SettingSchema::Boolean if value == "true" || value == "false" => Ok(()),
+        SettingSchema::Boolean => Err(format!("{key} has to be true or false.")),
+        SettingSchema::Integer { min, max } => {
+            let number: i64 = value
+                .parse()
+                .map_err(|_| format!("{key} has to be a whole number."))?;
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                let bound = |bound: &Option<i64>| bound.map(|b| b.to_string()).unwrap_or_default();
+                return Err(format!(
+                    "{key} has to be between {} and {}.",
+                    bound(min),
+                    bound(max)
+                ));
+            }
+            Ok(())
+        }
+        SettingSchema::Enum { values } if values.iter().any(|v| v == value) => Ok(()),
+        SettingSchema::Enum { values } => {
+            Err(format!("{key} has to be one of {}.", values.join(", ")))
+        }
+    }
+}
+
+// .properties and .ini are edited line by line, so comments and order stay as they are.
+
+/// Key and the byte offset its value starts at, for a `key=value` or `key: value` line.
+fn parse_line(line: &str, comments: &[char]) -> Option<(String, usize)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with(comments) || trimmed.starts_with('[') {
+        return None;
+    }
+    let separator = line.find(['=', ':'])?;
+    let key = line[..separator].trim().to_string();
+    let value_start =
+        separator + 1 + line[separator + 1..].len() - line[separator + 1..].trim_start().len();
+    Some((key, value_start))
+}
+
+/// Section header of an ini line, e.g. "server" for "[server]".
+fn section(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.strip_prefix('[')?.strip_suffix(']').map(str::trim)
+}
+
+/// Section and key inside it of an ini key like "server.name". Keys before the first section have
+/// no section.
+fn split_ini_key<'a>(lines: &[&'a str], key: &'a str) -> (Option<&'a str>, &'a str) {
+    if let Some((section_name, name)) = key.split_once('.') {
+        if lines.iter().any(|line| section(line) == Some(section_name)) {
+            return (Some(section_name), name);
+        }
+    }
+    (None, key)
+}
+
+/// All keys and values of a line based file, ini keys prefixed with their section.
+fn lines_list(content: &str, format: SettingsFormat) -> Vec<(String, String)> {
+    let comments: &[char] = match format {
+        SettingsFormat::Ini => &[';', '#'],
+        _ => &['#', '!'],
+    };
+    let mut current: Option<&str> = None;
+    let mut values: Vec<(String, String)> = vec![];
+    for line in content.lines() {
+        if let (SettingsFormat::Ini, Some(name)) = (format, section(line)) {
+            current = Some(name);
+        } else if let Some((key, value_start)) = parse_line(line, comments) {
+            let key = match current {
+                Some(section) => format!("{section}.{key}"),
+                None => key,
+            };
+            values.push((key, line[value_start..].trim_end().to_string()));
+        }
+    }
+    values
+}
+
+/// Replaces the value of a key in a line based file, appending it if it is missing.
+fn lines_set(content: &str, format: SettingsFormat, key: &str, value: &str) -> String {
+    let comments: &[char] = match format {
+        SettingsFormat::Ini => &[';', '#'],
+        _ => &['#', '!'],
+    };
+    // keep windows line endings, lines() strips the \r
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let borrowed: Vec<&str> = content.lines().collect();
+    let (wanted_section, name) = match format {
+        SettingsFormat::Ini => split_ini_key(&borrowed, key),
+        _ => (None, key),
+    };
+
+    let mut current: Option<&str> = None;
+    // index after the last line of the wanted section, where a missing key is inserted
+    let mut insert_at: Option<usize> = if wanted_section.is_none() {
+        // top level keys go before the first section, if there are none yet
+        Some(
+            borrowed
+                .iter()
+                .position(|line| format == SettingsFormat::Ini && section(line).is_some())
+                .unwrap_or(borrowed.len()),
+        )
+    } else {
+        None
+    };
+    for (index, line) in borrowed.iter().enumerate() {
+        if let (SettingsFormat::Ini, Some(section_name)) = (format, section(line)) {
+            current = Some(section_name);
+            continue;
+        }
+        if current != wanted_section {
+            continue;
+        }
+        if !line.trim().is_empty() {
+            insert_at = Some(index + 1);
+        }
+        if let Some((line_key, value_start)) = parse_line(line, comments) {
+            if line_key == name {
+                lines[index] = format!("{}{value}", &line[..value_start]);
+                return lines.join(newline) + newline;
+            }
+        }
+    }
+
+    match (insert_at, wanted_section) {
+        (Some(index), _) => lines.insert(index, format!("{name}={value}")),
+        // the section exists but is empty
+        (None, Some(section_name)) => {
+            let index = borrowed
+                .iter()
+                .position(|line| section(line) == Some(section_name))
+                .map(|index| index + 1)
+                .unwrap_or(lines.len());
+            lines.insert(index, format!("{name}={value}"));
+        }
+        (None, None) => lines.push(format!("{name}={value}")),
+    }
+    lines.join(newline) + newline
+}
+
+// json keeps its order (serde_json is built with preserve_order), but can't have comments.
+
+/// Nested keys are separated by dots, e.g. "world.difficulty".
+fn json_get<'a>(json: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(json, |value, part| value.get(part))
+}
+
+fn json_list(json: &Value, prefix: &str, values: &mut Vec<(String, String)>) {
+    match json {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                json_list(value, &key, values);
+            }
+        }
+        Value::String(value) => values.push((prefix.to_string(), value.clone())),
+        value => values.push((prefix.to_string(), value.to_string())),
+    }
+}
+
+fn json_set(json: &mut Value, key: &str, value: &str) -> Result<(), String> {
+    let (parents, name) = match key.rsplit_once('.') {
+        Some((parents, name)) => (Some(parents), name),
+        None => (None, key),
+    };
+    let parent = match parents {
+        Some(parents) => parents
+            .split('.')
+            .try_fold(&mut *json, |value, part| value.get_mut(part)),
+        None => Some(json),
+    };
+    let Some(Value::Object(parent)) = parent else {
+        return Err(format!("{key} can't be set, its parent is not an object."));
+    };
+
+    // strings stay strings, anything else is parsed, e.g. numbers and booleans
+    let new_value = match parent.get(name) {
+        Some(Value::String(_)) => Value::String(value.to_string()),
+        _ => serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string())),
+    };
+    parent.insert(name.to_string(), new_value);
+    Ok(())
+}
+
+/// Which declared file a key belongs to, and the key inside it. "file:key" picks the file.
+fn locate<'a>(
+    server: &str,
+    files: &'a [SettingsFile],
+    key: &'a str,
+) -> Result<(&'a SettingsFile, &'a str), String> {
+    if let Some((file, name)) = key.split_once(':') {
+        if let Some(settings) = files.iter().find(|settings| settings.file == file) {
+            return Ok((settings, name));
+        }
+    }
+    match files {
+        [] => Err("No settings are declared in config.json.".to_string()),
+        [settings] => Ok((settings, key)),
+        _ => files
+            .iter()
+            .find(|settings| {
+                settings
+                    .schema
+                    .as_ref()
+                    .map(|schema| schema.contains_key(key))
+                    .unwrap_or(false)
+                    || list_file(server, settings)
+                        .map(|values| values.iter().any(|(k, _)| k == key))
+                        .unwrap_or(false)
+            })
+            .map(|settings| (settings, key))
+            .ok_or(format!(
+                "{key} was not found, use file:key to pick one of {}.",
+                files
+                    .iter()
+                    .map(|settings| settings.file.clone())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+    }
+}
+
+fn read_file(server: &str, settings: &SettingsFile) -> Result<String, String> {
+    fs::read_to_string(path(server, &settings.file))
+        .map_err(|why| format!("Could not read {}: {why}", settings.file))
+}
+
+fn list_file(server: &str, settings: &SettingsFile) -> Result<Vec<(String, String)>, String> {
+    let content = read_file(server, settings)?;
+    match format_of(settings)? {
+        SettingsFormat::Json => {
+            let json: Value = serde_json::from_str(&content)
+                .map_err(|why| format!("Could not parse {}: {why}", settings.file))?;
+            let mut values = vec![];
+            json_list(&json, "", &mut values);
+            Ok(values)
+        }
+        format => Ok(lines_list(&content, format)),
+    }
+}
+
+/// All settings of the declared files, with the file they are in. Only keys of the schema are
+/// listed for files that have one.
+pub fn list(server: &str, files: &[SettingsFile]) -> Result<Vec<(String, String, String)>, String> {
+    let mut all = vec![];
+    for settings in files {
+        for (key, value) in list_file(server, settings)? {
+            let in_schema = settings
+                .schema
+                .as_ref()
+                .map(|schema| schema.contains_key(&key))
+                .unwrap_or(true);
+            if in_schema {
+                all.push((settings.file.clone(), key, value));
+            }
+        }
+    }
+    Ok(all)
+}
+
+/// Current value of a key, None if it is not set.
+pub fn get(server: &str, files: &[SettingsFile], key: &str) -> Result<Option<String>, String> {
+    let (settings, key) = locate(server, files, key)?;
+    if let SettingsFormat::Json = format_of(settings)? {
+        let json: Value = serde_json::from_str(&read_file(server, settings)?)
+            .map_err(|why| format!("Could not parse {}: {why}", settings.file))?;
+        return Ok(json_get(&json, key).map(|value| match value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }));
+    }
+    Ok(list_file(server, settings)?
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value))
+}
+
+/// Validates and sets a value, returning the file it was written to.
+pub fn set(server: &str, files: &[SettingsFile], key: &str, value: &str) -> Result<String, String> {
+    let (settings, key) = locate(server, files, key)?;
+    validate(settings, key, value)?;
+    if value.contains(['\n', '\r']) {
+        return Err("Values can't span several lines.".to_string());
+    }
+
+    let content = read_file(server, settings)?;
+    let content = match format_of(settings)? {
+        SettingsFormat::Json => {
+            let mut json: Value = serde_json::from_str(&content)
+                .map_err(|why| format!("Could not parse {}: {why}", settings.file))?;
+            json_set(&mut json, key, value)?;
+            serde_json::to_string_pretty(&json).expect("failed to serialize settings.") + "\n"
+        }
+        format => lines_set(&content, format, key, value),
+    };
+
+    write_atomically(&path(server, &settings.file), content)
+        .map_err(|why| format!("Could not write {}: {why}", settings.file))?;
+    Ok(settings.file.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const PROPERTIES: &str = "\
+# Minecraft server properties
+difficulty=easy
+! also a comment
+motd: A Minecraft Server
+max-players=20
+";
+
+    const INI: &str = "\
+; top level
+name=example
+
+[server]
+port = 2456
+public=1
+
+[world]
+seed=abc
+";
+
+    #[test]
+    fn lines_list_skips_comments_and_keeps_order() {
+        assert_eq!(
+            lines_list(PROPERTIES, SettingsFormat::Properties),
+            vec![
+                ("difficulty".to_string(), "easy".to_string()),
+                ("motd".to_string(), "A Minecraft Server".to_string()),
+                ("max-players".to_string(), "20".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_list_prefixes_ini_sections() {
+        assert_eq!(
+            lines_list(INI, SettingsFormat::Ini),
+            vec![
+                ("name".to_string(), "example".to_string()),
+                ("server.port".to_string(), "2456".to_string()),
+                ("server.public".to_string(), "1".to_string()),
+                ("world.seed".to_string(), "abc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_set_replaces_value_in_place() {
+        assert_eq!(
+            lines_set(PROPERTIES, SettingsFormat::Properties, "motd", "Hello"),
+            PROPERTIES.replace("motd: A Minecraft Server", "motd: Hello")
+        );
+        assert_eq!(
+            lines_set(PROPERTIES, SettingsFormat::Properties, "difficulty", "hard"),
+            PROPERTIES.replace("difficulty=easy", "difficulty=hard")
+        );
+    }
+
+    #[test]
+    fn lines_set_appends_missing_key() {
+        assert_eq!(
+            lines_set(PROPERTIES, SettingsFormat::Properties, "pvp", "false"),
+            format!("{PROPERTIES}pvp=false\n")
+        );
+    }
+
+    #[test]
+    fn lines_set_replaces_value_in_section() {
+        assert_eq!(
+            lines_set(INI, SettingsFormat::Ini, "server.port", "2457"),
+            INI.replace("port = 2456", "port = 2457")
+        );
+        // the same key in another section is left alone
+        assert_eq!(
+            lines_set(INI, SettingsFormat::Ini, "world.seed", "xyz"),
+            INI.replace("seed=abc", "seed=xyz")
+        );
+    }
+
+    #[test]
+    fn lines_set_inserts_missing_key_at_end_of_section() {
+        assert_eq!(
+            lines_set(INI, SettingsFormat::Ini, "server.password", "secret"),
+            INI.replace("public=1\n", "public=1\npassword=secret\n")
+        );
+    }
+
+    #[test]
+    fn lines_set_inserts_top_level_key_before_first_section() {
+        assert_eq!(
+            lines_set(INI, SettingsFormat::Ini, "log", "true"),
+            INI.replace("name=example\n", "name=example\nlog=true\n")
+        );
+        assert_eq!(
+            lines_set("[server]\nport=2456\n", SettingsFormat::Ini, "log", "true"),
+            "log=true\n[server]\nport=2456\n"
+        );
+    }
+
+    #[test]
+    fn lines_set_keeps_windows_line_endings() {
+        let content = INI.replace('\n', "\r\n");
+        assert_eq!(
+            lines_set(&content, SettingsFormat::Ini, "server.port", "2457"),
+            content.replace("2456", "2457")
+        );
+        assert_eq!(
+            lines_set(&content, SettingsFormat::Ini, "server.password", "secret"),
+            content.replace("public=1\r\n", "public=1\r\npassword=secret\r\n")
+        );
+    }
+
+    #[test]
+    fn lines_set_fills_empty_section() {
+        let content = "[server]\n[world]\nseed=abc\n";
+        assert_eq!(
+            lines_set(content, SettingsFormat::Ini, "server.port", "2456"),
+            "[server]\nport=2456\n[world]\nseed=abc\n"
+        );
+    }
+
+    #[test]
+    fn json_set_keeps_types_and_order() {
+        let mut value = json!({
+            "name": "example",
+            "world": { "difficulty": "normal", "size": 10 },
+            "public": true
+        });
+        json_set(&mut value, "world.size", "20").unwrap();
+        json_set(&mut value, "world.difficulty", "hard").unwrap();
+        json_set(&mut value, "name", "123").unwrap();
+        json_set(&mut value, "public", "false").unwrap();
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"123","world":{"difficulty":"hard","size":20},"public":false}"#
+        );
+    }
+
+    #[test]
+    fn json_set_adds_missing_key() {
+        let mut value = json!({ "world": {} });
+        json_set(&mut value, "world.seed", "abc").unwrap();
+        assert_eq!(value, json!({ "world": { "seed": "abc" } }));
+    }
+
+    #[test]
+    fn json_set_rejects_non_object_parent() {
+        let mut value = json!({ "name": "example" });
+        assert!(json_set(&mut value, "name.first", "x").is_err());
+        assert!(json_set(&mut value, "missing.key", "x").is_err());
+    }
+}
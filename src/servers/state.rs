@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Keeps track of which servers the bot started, so it knows after a restart.
+const STATE_FILE: &str = "./cache/servers.json";
+
+/// Serializes read-modify-write cycles of the state file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// What the bot knows about a gameserver beyond its status script.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ServerRecord {
+    pub running: bool,
+    /// Unix timestamp, None if the server was started by something other than the bot.
+    pub started_at: Option<u64>,
+    /// Discord user ids of whoever started it, comma separated for votes.
+    pub started_by: Option<String>,
+    /// Pid of the server process, if it is supervised.
+    pub pid: Option<u32>,
+}
+
+impl ServerRecord {
+    pub fn uptime(&self) -> Option<Duration> {
+        let started_at = self.started_at.filter(|_| self.running)?;
+        Some(Duration::from_secs(now().saturating_sub(started_at)))
+    }
+
+    /// Mentions of the users who started the server.
+    pub fn started_by_mentions(&self) -> Option<String> {
+        let started_by = self.started_by.as_ref().filter(|_| self.running)?;
+        let mentions: Vec<String> = started_by.split(',').map(|id| format!("<@{id}>")).collect();
+        Some(mentions.join(", "))
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs()
+}
+
+fn read() -> HashMap<String, ServerRecord> {
+    match fs::read_to_string(STATE_FILE) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|why| {
+            println!("could not parse {STATE_FILE}: {why}");
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn write(records: &HashMap<String, ServerRecord>) {
+    if let Some(parent) = Path::new(STATE_FILE).parent() {
+        fs::create_dir_all(parent).expect("failed to create parent dir for state file.");
+    }
+    let data = serde_json::to_string_pretty(records).expect("failed to serialize server state.");
+    fs::write(STATE_FILE, data).expect("failed to write server state.");
+}
+
+/// Returns the record of a server, a default one if nothing is known about it.
+pub fn get(server: &str) -> ServerRecord {
+    let _lock = LOCK.lock().expect("state lock poisoned");
+    read().remove(server).unwrap_or_default()
+}
+
+/// Returns the records of all servers the bot knows something about.
+pub fn all() -> HashMap<String, ServerRecord> {
+    let _lock = LOCK.lock().expect("state lock poisoned");
+    read()
+}
+
+fn update(server: &str, change: impl FnOnce(&mut ServerRecord)) {
+    let _lock = LOCK.lock().expect("state lock poisoned");
+    let mut records = read();
+    change(records.entry(server.to_string()).or_default());
+    write(&records);
+}
+
+/// Records that the bot started a server.
+pub fn record_start(server: &str, started_by: Option<&str>, pid: Option<u32>) {
+    update(server, |record| {
+        *record = ServerRecord {
+            running: true,
+            started_at: Some(now()),
+            started_by: started_by.map(str::to_string),
+            pid,
+        };
+    });
+}
+
+/// Records that a server stopped, attributing its runtime to whoever started it.
+pub fn record_stop(server: &str) {
+    update(server, |record| {
+        if let (true, Some(started_at), Some(started_by)) =
+            (record.running, record.started_at, &record.started_by)
+        {
+            super::usage::record(started_by, started_at, now());
+        }
+        *record = ServerRecord::default()
+    });
+}
+
+/// Records a server found running which the bot doesn't know to be running.
+pub fn record_discovered(server: &str) {
+    update(server, |record| {
+        if !record.running {
+            *record = ServerRecord {
+                running: true,
+                ..ServerRecord::default()
+            };
+        }
+    });
+}
+
+/// Whether a process with this pid exists.
+pub fn pid_alive(pid: u32) -> bool {
+    Path::new(&format!("/proc/{pid}")).exists()
+}
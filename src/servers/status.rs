@@ -0,0 +1,232 @@
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::{Map, Value};
+
+use super::config::{self, QueryConfig, ServerConfig};
+use super::{path, query};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServerState {
+    Running,
+    Stopped,
+    /// No status.sh, or it could not be executed.
+    Unknown,
+}
+
+impl fmt::Display for ServerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerState::Running => write!(f, "🟢 running"),
+            ServerState::Stopped => write!(f, "🔴 stopped"),
+            ServerState::Unknown => write!(f, "⚪ unknown"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ServerStatus {
+    pub state: ServerState,
+    pub players: Option<u32>,
+    pub max_players: Option<u32>,
+    pub map: Option<String>,
+    pub version: Option<String>,
+    /// Any other fields of a json status, in the order they were given.
+    pub extra: Vec<(String, String)>,
+    /// Whether status.sh printed a json status, see gameservers/README.md.
+    pub structured: bool,
+    /// Raw stdout of status.sh.
+    pub output: String,
+}
+
+impl ServerStatus {
+    fn unknown() -> Self {
+        ServerStatus {
+            state: ServerState::Unknown,
+            players: None,
+            max_players: None,
+            map: None,
+            version: None,
+            extra: vec![],
+            structured: false,
+            output: String::new(),
+        }
+    }
+
+    /// Status of a server known to be stopped without probing it, `output` is shown as is.
+    pub fn stopped(output: &str) -> Self {
+        ServerStatus {
+            state: ServerState::Stopped,
+            output: output.to_string(),
+            ..ServerStatus::unknown()
+        }
+    }
+
+    /// Fills in the fields of a json status. Fields with unexpected types are ignored.
+    fn apply_json(&mut self, mut json: Map<String, Value>) {
+        self.structured = true;
+
+        if let Some(state) = json.remove("state") {
+            self.state = match state.as_str() {
+                Some("running") | Some("online") => ServerState::Running,
+                Some("stopped") | Some("offline") => ServerState::Stopped,
+                _ => ServerState::Unknown,
+            };
+        }
+        let count = |value: Option<Value>| value.and_then(|v| v.as_u64()).map(|v| v as u32);
+        self.players = count(json.remove("players"));
+        self.max_players = count(json.remove("max_players"));
+        let text = |value: Option<Value>| value.and_then(|v| v.as_str().map(str::to_string));
+        self.map = text(json.remove("map"));
+        self.version = text(json.remove("version"));
+
+        for (key, value) in json {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            self.extra.push((key, value));
+        }
+    }
+
+    /// Player count formatted as "3/10", "3" or "?" depending on what is known.
+    pub fn players_display(&self) -> String {
+        match (self.players, self.max_players) {
+            (Some(players), Some(max)) => format!("{players}/{max}"),
+            (Some(players), None) => players.to_string(),
+            _ => "?".to_string(),
+        }
+    }
+}
+
+/// Asks the server itself using the query protocol from config.json.
+/// Servers not answering are stopped, unless they are supervised, then their process decides.
+fn query_status(config: &ServerConfig, query_config: &QueryConfig) -> ServerStatus {
+    let mut status = ServerStatus::unknown();
+    match query::query(query_config) {
+        Ok(info) => {
+            status.state = ServerState::Running;
+            status.structured = true;
+            status.players = info.players;
+            status.max_players = info.max_players;
+            status.map = info.map;
+            status.version = info.version;
+        }
+        Err(why) => {
+            // output is shown as the status, "stopped" says more than the connect error
+            println!("could not query {}: {why}", config.name);
+            if !config.supervised {
+                status.state = ServerState::Stopped;
+            }
+        }
+    }
+    status
+}
+
+/// Runs the status script of a gameserver.
+/// If it prints a json object, that is used for the status. Otherwise the exit code decides
+/// whether the server is running and stdout is kept as is.
+/// Without a status script, the server is queried if config.json says how.
+pub fn probe(server: &str) -> ServerStatus {
+    let script = path(server, "status.sh");
+
+    if !Path::new(&script).exists() {
+        let config = config::load(server);
+        return match &config.query {
+            Some(query_config) => query_status(&config, query_config),
+            None => ServerStatus::unknown(),
+        };
+    }
+
+    match Command::new("bash").arg(&script).output() {
+        Ok(output) => {
+            let mut status = ServerStatus::unknown();
+            status.state = if output.status.success() {
+                ServerState::Running
+            } else {
+                ServerState::Stopped
+            };
+            status.output = String::from_utf8_lossy(&output.stdout).to_string();
+
+            if let Ok(json) = serde_json::from_str::<Map<String, Value>>(status.output.trim()) {
+                status.apply_json(json);
+            }
+            status
+        }
+        Err(why) => {
+            println!("could not run {script}: {why}");
+            ServerStatus::unknown()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn applied(value: Value) -> ServerStatus {
+        let mut status = ServerStatus::unknown();
+        match value {
+            Value::Object(json) => status.apply_json(json),
+            _ => panic!("not an object"),
+        }
+        status
+    }
+
+    #[test]
+    fn apply_json_reads_known_fields() {
+        let status = applied(json!({
+            "state": "online",
+            "players": 3,
+            "max_players": 10,
+            "map": "de_dust2",
+            "version": "1.2"
+        }));
+        assert!(status.structured);
+        assert_eq!(status.state, ServerState::Running);
+        assert_eq!(status.players_display(), "3/10");
+        assert_eq!(status.map.as_deref(), Some("de_dust2"));
+        assert_eq!(status.version.as_deref(), Some("1.2"));
+        assert!(status.extra.is_empty());
+    }
+
+    #[test]
+    fn apply_json_states() {
+        for (state, expected) in [
+            ("running", ServerState::Running),
+            ("stopped", ServerState::Stopped),
+            ("offline", ServerState::Stopped),
+            ("starting", ServerState::Unknown),
+        ] {
+            assert_eq!(applied(json!({ "state": state })).state, expected);
+        }
+        // no state leaves it as it was
+        assert_eq!(applied(json!({})).state, ServerState::Unknown);
+    }
+
+    #[test]
+    fn apply_json_ignores_unexpected_types() {
+        let status = applied(json!({ "players": "many", "max_players": -1, "map": 5 }));
+        assert_eq!(status.players, None);
+        assert_eq!(status.max_players, None);
+        assert_eq!(status.map, None);
+        assert_eq!(status.players_display(), "?");
+    }
+
+    #[test]
+    fn apply_json_keeps_other_fields_in_order() {
+        let status = applied(json!({ "uptime": "2h", "seed": 42, "players": 1, "pvp": true }));
+        assert_eq!(status.players_display(), "1");
+        assert_eq!(
+            status.extra,
+            vec![
+                ("uptime".to_string(), "2h".to_string()),
+                ("seed".to_string(), "42".to_string()),
+                ("pvp".to_string(), "true".to_string()),
+            ]
+        );
+    }
+}
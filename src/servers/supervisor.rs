@@ -0,0 +1,259 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use serenity::prelude::*;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{ChildStdin, Command};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use super::log::LogTail;
+use super::{config, limits, path, state};
+use crate::notifications;
+
+/// File in the servers folder the output of supervised servers is written to.
+pub const CONSOLE_LOG: &str = "console.log";
+
+/// Output lines buffered for slow subscribers before they miss some.
+const OUTPUT_BUFFER: usize = 256;
+/// How often the console log is checked for new output.
+const TAIL_INTERVAL: Duration = Duration::from_millis(250);
+/// Last output lines kept for the crash report.
+const CRASH_REPORT_LINES: usize = 10;
+
+/// A gameserver running as a child process of the bot.
+pub struct SupervisedProcess {
+    pub pid: u32,
+    stdin: ChildStdin,
+    /// Every line the process writes, for whoever wants to follow its console.
+    output: broadcast::Sender<String>,
+    /// Set once the bot asked the server to stop, so exiting isn't reported as a crash.
+    stopping: bool,
+}
+
+/// Gameservers started with `"supervised": true`, by folder name.
+pub struct Supervisor;
+
+impl TypeMapKey for Supervisor {
+    type Value = Arc<Mutex<HashMap<String, SupervisedProcess>>>;
+}
+
+async fn processes(ctx: &Context) -> Arc<Mutex<HashMap<String, SupervisedProcess>>> {
+    let data = ctx.data.read().await;
+    data.get::<Supervisor>()
+        .expect("Supervisor should be in the client data")
+        .clone()
+}
+
+/// Follows the console log and passes new lines on to subscribers, until `exited` is set.
+/// The process writes into the log directly rather than through a pipe, as it would be killed by
+/// SIGPIPE on its next write once the bot exits.
+fn follow_output(
+    log_path: String,
+    mut tail: LogTail,
+    subscribers: broadcast::Sender<String>,
+    recent: Arc<StdMutex<VecDeque<String>>>,
+    exited: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            // checked before reading, so the last output is read once the process exited
+            let done = exited.load(Ordering::Relaxed);
+            for line in tail.read_new_lines(&log_path) {
+                {
+                    let mut recent = recent.lock().expect("recent output lock poisoned");
+                    if recent.len() == CRASH_REPORT_LINES {
+                        recent.pop_front();
+                    }
+                    recent.push_back(line.clone());
+                }
+                // fails if nobody is subscribed, which is fine
+                subscribers.send(line).ok();
+            }
+            if done {
+                return;
+            }
+            tokio::time::sleep(TAIL_INTERVAL).await;
+        }
+    })
+}
+
+/// Posts why a server exited without being asked to, including limits it probably ran into.
+async fn report_crash(ctx: &Context, server: &str, status: &ExitStatus, recent: Vec<String>) {
+    let config = config::load(server);
+    let mut report = format!(
+        "**{}** crashed, it {}.",
+        config.name,
+        limits::describe_exit(status)
+    );
+    if let Some(exceeded) = limits::exceeded(status, &recent, &config.limits) {
+        report.push_str(&format!("\n{exceeded}"));
+    }
+    if !recent.is_empty() {
+        let output = notifications::escape_code_block(&recent.join("\n"));
+        report.push_str(&format!("\n```\n{output}\n```"));
+    }
+    notifications::send(ctx, &report).await;
+}
+
+/// Starts start.sh as a child process. The script should `exec` the server, so it stays in the
+/// foreground and receives console input and signals. The limits from config.json apply to it.
+pub async fn start(ctx: &Context, server: &str) -> Result<u32, String> {
+    let script = path(server, "start.sh");
+    if !Path::new(&script).exists() {
+        return Err(format!("{server} has no start.sh"));
+    }
+
+    let processes = processes(ctx).await;
+    let mut running = processes.lock().await;
+    if running.contains_key(server) {
+        return Err(format!("{server} is already running"));
+    }
+
+    let log_path = path(server, CONSOLE_LOG);
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|why| format!("could not open {log_path}: {why}"))?;
+    let log_stderr = log
+        .try_clone()
+        .map_err(|why| format!("could not open {log_path}: {why}"))?;
+    // only output written from now on is followed
+    let mut tail = LogTail::default();
+    tail.read_new_lines(&log_path);
+
+    let limits = config::load(server).limits;
+    let mut command = Command::new("bash");
+    command
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(log_stderr))
+        // own process group, so signals meant for the bot (e.g. ctrl-c) don't reach the server
+        .process_group(0);
+    // safe, as limits::apply only uses async-signal-safe functions
+    unsafe {
+        command.pre_exec(move || limits::apply(&limits));
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|why| format!("could not start {server}: {why}"))?;
+
+    let pid = child.id().ok_or(format!("{server} exited immediately"))?;
+    let stdin = child.stdin.take().expect("stdin should be piped");
+    let (output, _) = broadcast::channel(OUTPUT_BUFFER);
+    let recent = Arc::new(StdMutex::new(VecDeque::new()));
+    let exited = Arc::new(AtomicBool::new(false));
+    let follower = follow_output(
+        log_path,
+        tail,
+        output.clone(),
+        recent.clone(),
+        exited.clone(),
+    );
+
+    running.insert(
+        server.to_string(),
+        SupervisedProcess {
+            pid,
+            stdin,
+            output,
+            stopping: false,
+        },
+    );
+
+    // forget the process once it exits, and report it if nobody asked it to
+    let processes = processes.clone();
+    let server = server.to_string();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        let status = child.wait().await;
+        match &status {
+            Ok(status) => println!("{server} (pid {pid}) exited: {status}"),
+            Err(why) => println!("could not wait for {server} (pid {pid}): {why}"),
+        }
+        // pick up the last output, for the crash report
+        exited.store(true, Ordering::Relaxed);
+        follower.await.ok();
+
+        let crashed = {
+            let mut running = processes.lock().await;
+            match running.get(&server) {
+                Some(process) if process.pid == pid => {
+                    let crashed = !process.stopping;
+                    running.remove(&server);
+                    state::record_stop(&server);
+                    crashed
+                }
+                _ => false,
+            }
+        };
+
+        if let (true, Ok(status)) = (crashed, status) {
+            let recent = recent
+                .lock()
+                .expect("recent output lock poisoned")
+                .iter()
+                .cloned()
+                .collect();
+            report_crash(&ctx, &server, &status, recent).await;
+        }
+    });
+
+    Ok(pid)
+}
+
+/// Pid of a supervised server, None if it isn't running.
+pub async fn pid(ctx: &Context, server: &str) -> Option<u32> {
+    processes(ctx).await.lock().await.get(server).map(|p| p.pid)
+}
+
+/// Marks a supervised server as being stopped by the bot, so it exiting is not reported as a crash.
+pub async fn expect_exit(ctx: &Context, server: &str) {
+    if let Some(process) = processes(ctx).await.lock().await.get_mut(server) {
+        process.stopping = true;
+    }
+}
+
+/// Follows the output of a supervised server from now on, None if it isn't running.
+pub async fn subscribe(ctx: &Context, server: &str) -> Option<broadcast::Receiver<String>> {
+    let processes = processes(ctx).await;
+    let running = processes.lock().await;
+    running.get(server).map(|p| p.output.subscribe())
+}
+
+/// Writes a line to the stdin of a supervised server.
+pub async fn write_line(ctx: &Context, server: &str, line: &str) -> Result<(), String> {
+    let processes = processes(ctx).await;
+    let mut running = processes.lock().await;
+    let process = running
+        .get_mut(server)
+        .ok_or(format!("{server} is not running"))?;
+
+    process
+        .stdin
+        .write_all(format!("{line}\n").as_bytes())
+        .await
+        .map_err(|why| format!("could not write to {server}: {why}"))
+}
+
+/// Sends a signal (e.g. libc::SIGTERM) to the process group of a supervised server.
+/// Supervised servers get their own process group, so this reaches processes start.sh spawned too.
+pub fn signal(pid: u32, signal: i32) -> Result<(), String> {
+    // a negative pid addresses the process group
+    let result = unsafe { libc::kill(-(pid as libc::pid_t), signal) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "could not signal {pid}: {}",
+            std::io::Error::last_os_error()
+        ))
+    }
+}
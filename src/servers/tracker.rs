@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::prelude::*;
+
+use super::config::{self, ServerConfig};
+use super::health::{self, ServerHealth};
+use super::state::{self, ServerRecord};
+use super::status::{self, ServerState, ServerStatus};
+use super::supervisor;
+use crate::tasks::wake;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Last known config and status of a gameserver.
+#[derive(Clone, Debug)]
+pub struct TrackedServer {
+    /// Folder name, used to address the server in commands.
+    pub server: String,
+    pub config: ServerConfig,
+    pub status: ServerStatus,
+    pub record: ServerRecord,
+    /// Result of the health checks, if the server has some and is running.
+    pub health: Option<ServerHealth>,
+}
+
+/// Periodically probed state of all gameservers, shared through the client data.
+/// Reading from here avoids running every status script whenever something wants to display it.
+pub struct Tracker;
+
+impl TypeMapKey for Tracker {
+    type Value = Arc<RwLock<HashMap<String, TrackedServer>>>;
+}
+
+/// Returns the tracked servers, sorted by folder name.
+pub async fn snapshot(ctx: &Context) -> Vec<TrackedServer> {
+    let tracker = {
+        let data = ctx.data.read().await;
+        data.get::<Tracker>()
+            .expect("Tracker should be in the client data")
+            .clone()
+    };
+
+    let mut servers: Vec<TrackedServer> = tracker.read().await.values().cloned().collect();
+    servers.sort_by(|a, b| a.server.cmp(&b.server));
+    servers
+}
+
+/// Whether a server the bot started may still be coming up, so it reporting stopped doesn't mean
+/// it went down. Its record is kept until then, with who started it and when.
+async fn booting(
+    ctx: &Context,
+    server: &str,
+    config: &ServerConfig,
+    record: &ServerRecord,
+) -> bool {
+    let within_timeout = record
+        .started_at
+        .is_some_and(|started_at| state::now() < started_at + config.ready_timeout);
+    let process_alive = match supervisor::pid(ctx, server).await {
+        Some(_) => true,
+        None => record.pid.is_some_and(state::pid_alive),
+    };
+    within_timeout || process_alive
+}
+
+/// Probes a single gameserver and brings the state file in line with the result.
+/// Supervised servers without a status script are considered running as long as their process is,
+/// which may still be alive from before the bot restarted.
+pub async fn probe(ctx: &Context, server: &str) -> TrackedServer {
+    let config = config::load(server);
+    let mut record = state::get(server);
+
+    // the port is held by the bot, which would answer a query itself
+    let mut status = if wake::listening(ctx, server).await {
+        ServerStatus::stopped("Sleeping, starts once someone connects.")
+    } else {
+        let script_server = server.to_string();
+        tokio::task::spawn_blocking(move || status::probe(&script_server))
+            .await
+            .expect("probing gameserver panicked")
+    };
+
+    if config.supervised && status.state == ServerState::Unknown {
+        let orphan_alive = record.pid.map(state::pid_alive).unwrap_or(false);
+        status.state = match supervisor::pid(ctx, server).await {
+            Some(_) => ServerState::Running,
+            None if orphan_alive => ServerState::Running,
+            None => ServerState::Stopped,
+        };
+    }
+
+    match (status.state, record.running) {
+        (ServerState::Running, false) => {
+            state::record_discovered(server);
+            record = state::get(server);
+        }
+        (ServerState::Stopped, true) if !booting(ctx, server, &config, &record).await => {
+            state::record_stop(server);
+            record = ServerRecord::default();
+        }
+        _ => {}
+    }
+
+    let health = match status.state {
+        ServerState::Running => health::get(ctx, server).await,
+        _ => None,
+    };
+
+    TrackedServer {
+        server: server.to_string(),
+        config,
+        status,
+        record,
+        health,
+    }
+}
+
+/// Probes all gameservers now and stores the result.
+pub async fn refresh(ctx: &Context) {
+    let mut probed: HashMap<String, TrackedServer> = HashMap::new();
+    for server in super::discover() {
+        probed.insert(server.clone(), probe(ctx, &server).await);
+    }
+
+    let data = ctx.data.read().await;
+    let tracker = data
+        .get::<Tracker>()
+        .expect("Tracker should be in the client data");
+    *tracker.write().await = probed;
+}
+
+/// Probes a single gameserver now and stores the result, e.g. right after starting or stopping it.
+pub async fn update(ctx: &Context, server: &str) {
+    let tracked = probe(ctx, server).await;
+    let data = ctx.data.read().await;
+    let tracker = data
+        .get::<Tracker>()
+        .expect("Tracker should be in the client data");
+    tracker.write().await.insert(server.to_string(), tracked);
+}
+
+/// Keeps the tracker up to date.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            refresh(&ctx).await;
+        }
+    });
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::state::{self, now};
+use super::{date, days_from_civil};
+
+/// Seconds each server ran per month and user who started it.
+const USAGE_FILE: &str = "./cache/usage.json";
+
+/// Serializes read-modify-write cycles of the usage file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Seconds of runtime by user id, by month (e.g. "2026-10").
+type Usage = HashMap<String, HashMap<String, u64>>;
+
+fn read() -> Usage {
+    match fs::read_to_string(USAGE_FILE) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|why| {
+            println!("could not parse {USAGE_FILE}: {why}");
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn write(usage: &Usage) {
+    if let Some(parent) = Path::new(USAGE_FILE).parent() {
+        fs::create_dir_all(parent).expect("failed to create parent dir for usage file.");
+    }
+    let data = serde_json::to_string_pretty(usage).expect("failed to serialize usage.");
+    fs::write(USAGE_FILE, data).expect("failed to write usage.");
+}
+
+/// Year and month of a unix timestamp (UTC).
+fn month_of(timestamp: u64) -> (i64, u32) {
+    let (year, month, _) = date(timestamp);
+    (year, month)
+}
+
+/// Unix timestamp the month starts at.
+fn month_start((year, month): (i64, u32)) -> u64 {
+    (days_from_civil(year, month, 1) * 86400) as u64
+}
+
+fn next_month((year, month): (i64, u32)) -> (i64, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
+fn month_key((year, month): (i64, u32)) -> String {
+    format!("{year}-{month:02}")
+}
+
+/// Unix timestamp the current month started at.
+pub fn current_month_start() -> u64 {
+    month_start(month_of(now()))
+}
+
+/// Unix timestamp the next month starts at, when quotas reset.
+pub fn next_month_start() -> u64 {
+    month_start(next_month(month_of(now())))
+}
+
+/// The users in a `started_by` of the state file, votes have several.
+fn starters(started_by: &str) -> Vec<&str> {
+    started_by.split(',').filter(|id| !id.is_empty()).collect()
+}
+
+/// Attributes the runtime from `from` to `to` to whoever started the server, split evenly if
+/// several users did and split by month if it crosses one.
+pub fn record(started_by: &str, from: u64, to: u64) {
+    let users = starters(started_by);
+    if users.is_empty() || to <= from {
+        return;
+    }
+
+    let _lock = LOCK.lock().expect("usage lock poisoned");
+    let mut usage = read();
+    add(&mut usage, &users, from, to);
+    write(&usage);
+}
+
+fn add(usage: &mut Usage, users: &[&str], from: u64, to: u64) {
+    let mut month = month_of(from);
+    let mut start = from;
+    while start < to {
+        let end = month_start(next_month(month)).min(to);
+        let share = (end - start) / users.len() as u64;
+        let months = usage.entry(month_key(month)).or_default();
+        for user in users {
+            *months.entry(user.to_string()).or_default() += share;
+        }
+        month = next_month(month);
+        start = end;
+    }
+}
+
+/// Runtime attributed to a user this month, including servers they started which still run.
+pub fn used_this_month(user: &str) -> Duration {
+    let month_start = current_month_start();
+    let stored = {
+        let _lock = LOCK.lock().expect("usage lock poisoned");
+        read()
+            .get(&month_key(month_of(month_start)))
+            .and_then(|users| users.get(user).copied())
+            .unwrap_or(0)
+    };
+
+    let running: u64 = state::all()
+        .values()
+        .filter(|record| record.running)
+        .filter_map(|record| {
+            let users = starters(record.started_by.as_deref()?);
+            if !users.contains(&user) {
+                return None;
+            }
+            let from = record.started_at?.max(month_start);
+            Some(now().saturating_sub(from) / users.len() as u64)
+        })
+        .sum();
+
+    Duration::from_secs(stored + running)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2026-10-31 23:00:00 UTC
+    const OCTOBER_END: u64 = 1793487600;
+
+    #[test]
+    fn months() {
+        assert_eq!(month_of(OCTOBER_END), (2026, 10));
+        assert_eq!(month_start((2026, 11)), OCTOBER_END + 3600);
+        assert_eq!(next_month((2026, 12)), (2027, 1));
+        assert_eq!(month_key((2027, 1)), "2027-01");
+    }
+
+    #[test]
+    fn starters_skip_empty_ids() {
+        assert_eq!(starters("1,2,,3"), vec!["1", "2", "3"]);
+        assert!(starters("").is_empty());
+    }
+
+    #[test]
+    fn add_within_month() {
+        let mut usage = Usage::new();
+        add(&mut usage, &["1"], OCTOBER_END - 600, OCTOBER_END);
+        add(&mut usage, &["1"], OCTOBER_END, OCTOBER_END + 60);
+        assert_eq!(usage["2026-10"]["1"], 660);
+        assert_eq!(usage.len(), 1);
+    }
+
+    #[test]
+    fn add_splits_by_month() {
+        let mut usage = Usage::new();
+        add(&mut usage, &["1"], OCTOBER_END, OCTOBER_END + 2 * 3600);
+        assert_eq!(usage["2026-10"]["1"], 3600);
+        assert_eq!(usage["2026-11"]["1"], 3600);
+    }
+
+    #[test]
+    fn add_shares_between_starters() {
+        let mut usage = Usage::new();
+        add(
+            &mut usage,
+            &starters("1,2"),
+            OCTOBER_END,
+            OCTOBER_END + 2 * 3600,
+        );
+        for user in ["1", "2"] {
+            assert_eq!(usage["2026-10"][user], 1800);
+            assert_eq!(usage["2026-11"][user], 1800);
+        }
+    }
+}
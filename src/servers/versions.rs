@@ -0,0 +1,57 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+use super::path;
+
+/// Folder inside the servers folder holding one folder per installed version.
+pub const VERSIONS_DIR: &str = "versions";
+/// Symlink inside the servers folder pointing at the active version, for start.sh to use.
+pub const CURRENT: &str = "current";
+
+/// Names of the installed versions of a server, sorted alphabetically.
+pub fn available(server: &str) -> Vec<String> {
+    let mut versions: Vec<String> = match fs::read_dir(path(server, VERSIONS_DIR)) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => vec![],
+    };
+    versions.sort();
+    versions
+}
+
+/// The version `current` points at, None if there is no such symlink.
+pub fn current(server: &str) -> Option<String> {
+    let target = fs::read_link(path(server, CURRENT)).ok()?;
+    target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// Points `current` at another version. The symlink is replaced by renaming a new one over it, so
+/// there is no moment without it.
+pub fn switch(server: &str, version: &str) -> Result<(), String> {
+    if !available(server).iter().any(|v| v == version) {
+        return Err(format!(
+            "{server} has no version called {version}. Installed: {}",
+            available(server).join(", ")
+        ));
+    }
+
+    let current_path = path(server, CURRENT);
+    if Path::new(&current_path).exists() && fs::read_link(&current_path).is_err() {
+        return Err(format!(
+            "{CURRENT} of {server} is not a symlink, move it into {VERSIONS_DIR} first."
+        ));
+    }
+
+    // relative, so the server folder can be moved
+    let temporary = path(server, &format!("{CURRENT}.new"));
+    fs::remove_file(&temporary).ok();
+    symlink(format!("{VERSIONS_DIR}/{version}"), &temporary)
+        .and_then(|_| fs::rename(&temporary, &current_path))
+        .map_err(|why| format!("Could not switch {server} to {version}: {why}"))
+}
@@ -0,0 +1,46 @@
+use serenity::all::{CreateAllowedMentions, CreateMessage, Message, ReactionType};
+use serenity::prelude::*;
+
+use crate::servers::config::{self, ChatConfig};
+use crate::servers::{self, console};
+
+/// Posts a chat message from the game into the chat channel.
+pub async fn announce(ctx: &Context, chat: &ChatConfig, player: &str, message: &str) {
+    let message = CreateMessage::new()
+        .content(format!("**{player}**: {message}"))
+        // players should not be able to ping everyone from in game
+        .allowed_mentions(CreateAllowedMentions::new());
+
+    if let Err(why) = chat.channel_id.send_message(&ctx.http, message).await {
+        println!("Cannot relay chat message: {why}");
+    }
+}
+
+/// Relays a discord message into the game, if it was sent in the chat channel of a gameserver.
+pub async fn relay(ctx: &Context, msg: &Message) {
+    if msg.author.bot || msg.content.is_empty() {
+        return;
+    }
+
+    for server in servers::discover() {
+        let Some(chat) = config::load(&server).chat else {
+            continue;
+        };
+        if chat.channel_id != msg.channel_id {
+            continue;
+        }
+
+        let line = chat
+            .say_format
+            .replace("{user}", msg.author.display_name())
+            .replace("{message}", &msg.content);
+
+        if let Err(why) = console::send(ctx, &server, &line).await {
+            println!("Cannot relay message to {server}: {why}");
+            // let the author know it didn't arrive
+            msg.react(&ctx.http, ReactionType::Unicode("⚠️".to_string()))
+                .await
+                .ok();
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    ChannelId, CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage, MessageId, Timestamp,
+};
+use serenity::prelude::*;
+
+use crate::servers::{format_duration, tracker};
+
+const DASHBOARD_FILE: &str = "./cache/dashboard.json";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Location of the dashboard message, persisted so it survives restarts.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct DashboardLocation {
+    channel_id: ChannelId,
+    message_id: MessageId,
+}
+
+fn load_location() -> Option<DashboardLocation> {
+    let content = fs::read_to_string(DASHBOARD_FILE).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_location(location: &DashboardLocation) {
+    if let Some(parent) = Path::new(DASHBOARD_FILE).parent() {
+        fs::create_dir_all(parent).expect("failed to create parent dir for dashboard file.");
+    }
+    let data = serde_json::to_string_pretty(location).expect("failed to serialize dashboard.");
+    fs::write(DASHBOARD_FILE, data).expect("failed to write dashboard file.");
+}
+
+async fn build_embed(ctx: &Context) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title("Gameservers")
+        .footer(CreateEmbedFooter::new("Updated every minute"))
+        .timestamp(Timestamp::now());
+
+    let servers = tracker::snapshot(ctx).await;
+    if servers.is_empty() {
+        return embed.description("No gameservers are available at this time.");
+    }
+
+    for tracked in servers {
+        let address = if tracked.config.ip.is_empty() {
+            "-".to_string()
+        } else {
+            tracked.config.ip.clone()
+        };
+
+        let mut value = format!(
+            "{}\nPlayers: {}\nAddress: `{}`",
+            tracked.status.state,
+            tracked.status.players_display(),
+            address
+        );
+        if let Some(health) = &tracked.health {
+            value.push_str(&format!("\nHealth: {}", health.state));
+        }
+        if let Some(uptime) = tracked.record.uptime() {
+            value.push_str(&format!("\nUp for {}", format_duration(uptime)));
+        }
+
+        embed = embed.field(tracked.config.name, value, false);
+    }
+
+    embed
+}
+
+/// Posts a new dashboard into the given channel, replacing the previous one.
+pub async fn create(ctx: &Context, channel_id: ChannelId) -> Result<(), String> {
+    let embed = build_embed(ctx).await;
+
+    let message = channel_id
+        .send_message(&ctx.http, CreateMessage::new().embed(embed))
+        .await
+        .map_err(|why| format!("could not post dashboard: {why}"))?;
+
+    if let Err(why) = channel_id.pin(&ctx.http, message.id).await {
+        // pinning needs the manage messages permission, the dashboard works without it.
+        println!("could not pin dashboard: {why}");
+    }
+
+    save_location(&DashboardLocation {
+        channel_id,
+        message_id: message.id,
+    });
+
+    Ok(())
+}
+
+async fn refresh(ctx: &Context) {
+    let Some(location) = load_location() else {
+        return; // no dashboard was created yet
+    };
+
+    let embed = build_embed(ctx).await;
+
+    if let Err(why) = location
+        .channel_id
+        .edit_message(
+            &ctx.http,
+            location.message_id,
+            EditMessage::new().embed(embed),
+        )
+        .await
+    {
+        println!("could not update dashboard: {why}");
+    }
+}
+
+/// Keeps the dashboard message up to date.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            refresh(&ctx).await;
+        }
+    });
+}
@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use crate::servers::rotation;
+
+const INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Rotates and archives the gameserver logs, see gameservers/README.md.
+pub fn spawn() {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(why) = tokio::task::spawn_blocking(rotation::rotate_all).await {
+                println!("rotating logs panicked: {why}");
+            }
+        }
+    });
+}
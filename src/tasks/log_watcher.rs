@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serenity::prelude::*;
+
+use super::chat_bridge;
+use crate::notifications;
+use crate::servers::config::{self, ChatConfig, LogConfig};
+use crate::servers::log::LogTail;
+use crate::servers::{self, path};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Compiles an optional pattern from config.json, reporting broken ones.
+fn compile(server: &str, pattern: &Option<String>) -> Option<Regex> {
+    let pattern = pattern.as_ref()?;
+    match Regex::new(pattern) {
+        Ok(regex) => Some(regex),
+        Err(why) => {
+            println!("invalid log pattern for {server}: {why}");
+            None
+        }
+    }
+}
+
+/// Player name from the `player` group of a match, or the first group.
+fn player(regex: &Regex, line: &str) -> Option<String> {
+    let captures = regex.captures(line)?;
+    captures
+        .name("player")
+        .or_else(|| captures.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Something worth posting, found in a log.
+enum LogEvent {
+    Announcement(String),
+    Chat { player: String, message: String },
+}
+
+/// Watch state of one servers log.
+struct WatchedLog {
+    config: LogConfig,
+    chat_config: Option<ChatConfig>,
+    tail: LogTail,
+    join: Option<Regex>,
+    leave: Option<Regex>,
+    chat: Option<Regex>,
+    /// Leaves are only announced once the reconnect window passed without a join.
+    pending_leaves: HashMap<String, Instant>,
+}
+
+impl WatchedLog {
+    fn new(server: &str, config: LogConfig, chat_config: Option<ChatConfig>) -> Self {
+        WatchedLog {
+            join: compile(server, &config.join_pattern),
+            leave: compile(server, &config.leave_pattern),
+            chat: compile(server, &chat_config.as_ref().map(|c| c.pattern.clone())),
+            config,
+            chat_config,
+            tail: LogTail::default(),
+            pending_leaves: HashMap::new(),
+        }
+    }
+
+    /// Reads new log lines and returns what should be posted.
+    fn poll(&mut self, server: &str, name: &str) -> Vec<LogEvent> {
+        let mut events: Vec<LogEvent> = vec![];
+
+        for line in self.tail.read_new_lines(&path(server, &self.config.file)) {
+            // chat first, so players can't fake joins by chatting
+            if let Some(captures) = self.chat.as_ref().and_then(|chat| chat.captures(&line)) {
+                if let (Some(player), Some(message)) =
+                    (captures.name("player"), captures.name("message"))
+                {
+                    events.push(LogEvent::Chat {
+                        player: player.as_str().to_string(),
+                        message: message.as_str().to_string(),
+                    });
+                }
+            } else if let Some(player) = self.join.as_ref().and_then(|join| player(join, &line)) {
+                // a pending leave means this is a reconnect, neither is announced
+                if self.pending_leaves.remove(&player).is_none() {
+                    events.push(LogEvent::Announcement(format!("{player} joined {name}")));
+                }
+            } else if let Some(player) = self.leave.as_ref().and_then(|leave| player(leave, &line))
+            {
+                self.pending_leaves.insert(player, Instant::now());
+            }
+        }
+
+        let window = Duration::from_secs(self.config.reconnect_window);
+        self.pending_leaves.retain(|player, left| {
+            if left.elapsed() >= window {
+                events.push(LogEvent::Announcement(format!("{player} left {name}")));
+                false
+            } else {
+                true
+            }
+        });
+
+        events
+    }
+}
+
+/// Announces players joining and leaving in the notification channel and mirrors the in-game chat,
+/// based on the server logs.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut watched: HashMap<String, WatchedLog> = HashMap::new();
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            for server in servers::discover() {
+                let config = config::load(&server);
+                let Some(log_config) = config.log.clone() else {
+                    watched.remove(&server);
+                    continue;
+                };
+
+                // (re)start watching if the config is new or was changed
+                if watched
+                    .get(&server)
+                    .map(|w| w.config != log_config || w.chat_config != config.chat)
+                    .unwrap_or(true)
+                {
+                    let log = WatchedLog::new(&server, log_config, config.chat.clone());
+                    watched.insert(server.clone(), log);
+                }
+
+                let log = watched.get_mut(&server).expect("log was just inserted");
+                for event in log.poll(&server, &config.name) {
+                    match (event, &log.chat_config) {
+                        (LogEvent::Announcement(announcement), _) => {
+                            notifications::send(&ctx, &announcement).await
+                        }
+                        (LogEvent::Chat { player, message }, Some(chat)) => {
+                            chat_bridge::announce(&ctx, chat, &player, &message).await
+                        }
+                        (LogEvent::Chat { .. }, None) => {}
+                    }
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,6 @@
+pub mod chat_bridge;
+pub mod dashboard;
+pub mod log_rotation;
+pub mod log_watcher;
+pub mod presence;
+pub mod wake;
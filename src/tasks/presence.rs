@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use serenity::all::ActivityData;
+use serenity::prelude::*;
+
+use crate::servers::status::ServerState;
+use crate::servers::tracker;
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Activity text for the running servers, e.g. "Valheim · 3/10", or None if nothing runs.
+async fn activity_text(ctx: &Context) -> Option<String> {
+    let running: Vec<String> = tracker::snapshot(ctx)
+        .await
+        .into_iter()
+        .filter(|tracked| tracked.status.state == ServerState::Running)
+        .map(|tracked| {
+            if tracked.status.players.is_some() {
+                format!(
+                    "{} · {}",
+                    tracked.config.name,
+                    tracked.status.players_display()
+                )
+            } else {
+                tracked.config.name
+            }
+        })
+        .collect();
+
+    if running.is_empty() {
+        None
+    } else {
+        Some(running.join(", "))
+    }
+}
+
+/// Shows the running gameservers as the bots activity.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPDATE_INTERVAL);
+        // None means the activity was never set, so the first update always goes through.
+        let mut current: Option<Option<String>> = None;
+
+        loop {
+            interval.tick().await;
+
+            let text = activity_text(&ctx).await;
+            if current.as_ref() == Some(&text) {
+                continue; // avoid needless presence updates
+            }
+
+            ctx.set_activity(text.clone().map(ActivityData::playing));
+            current = Some(text);
+        }
+    });
+}
@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use serenity::prelude::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::commands::gameserver;
+use crate::notifications;
+use crate::servers::config::{self, WakeConfig, WakeProtocol};
+use crate::servers::query::{packet, read_varint, write_varint};
+use crate::servers::status::ServerState;
+use crate::servers::tracker;
+
+const INTERVAL: Duration = Duration::from_secs(10);
+/// Time a client gets to send its handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Listeners holding the ports of stopped servers, by folder name.
+#[derive(Default)]
+pub struct WakeState {
+    listeners: HashMap<String, JoinHandle<()>>,
+    /// When the port was last released for the server to start, it is not taken again until the
+    /// server had time to come up.
+    released: HashMap<String, Instant>,
+}
+
+pub struct Wake;
+
+impl TypeMapKey for Wake {
+    type Value = Arc<Mutex<WakeState>>;
+}
+
+async fn wake_state(ctx: &Context) -> Arc<Mutex<WakeState>> {
+    let data = ctx.data.read().await;
+    data.get::<Wake>()
+        .expect("Wake should be in the client data")
+        .clone()
+}
+
+/// Whether the bot holds the port of a server, which means it is stopped.
+pub async fn listening(ctx: &Context, server: &str) -> bool {
+    let state = wake_state(ctx).await;
+    let state = state.lock().await;
+    state
+        .listeners
+        .get(server)
+        .is_some_and(|listener| !listener.is_finished())
+}
+
+/// Stops listening on the port of a server, so it can bind it itself. Called before every start.
+pub async fn release(ctx: &Context, server: &str) {
+    let state = wake_state(ctx).await;
+    let mut state = state.lock().await;
+    state.released.insert(server.to_string(), Instant::now());
+    if let Some(listener) = state.listeners.remove(server) {
+        listener.abort();
+        // the socket is closed once the task is gone
+        listener.await.ok();
+    }
+}
+
+/// A minecraft string, prefixed with its length.
+fn write_string(buffer: &mut Vec<u8>, string: &str) {
+    write_varint(buffer, string.len() as i32);
+    buffer.extend(string.as_bytes());
+}
+
+fn read_string(cursor: &mut Cursor<Vec<u8>>) -> Result<String, String> {
+    let length = read_varint(cursor)?;
+    let mut string = vec![0u8; length.clamp(0, 1024) as usize];
+    // std Read, as tokios AsyncReadExt is implemented for Cursor as well
+    Read::read_exact(cursor, &mut string).map_err(|why| format!("could not read packet: {why}"))?;
+    Ok(String::from_utf8_lossy(&string).to_string())
+}
+
+/// Reads a packet, returning its id and a cursor over the rest of it.
+async fn read_packet(stream: &mut TcpStream) -> Result<(i32, Cursor<Vec<u8>>), String> {
+    // the length is read byte by byte, as it is a varint
+    let mut length_bytes: Vec<u8> = vec![];
+    loop {
+        let byte = stream
+            .read_u8()
+            .await
+            .map_err(|why| format!("could not read packet: {why}"))?;
+        length_bytes.push(byte);
+        if byte & 0x80 == 0 || length_bytes.len() == 5 {
+            break;
+        }
+    }
+    let length = read_varint(&mut Cursor::new(length_bytes))?;
+    if !(1..=4096).contains(&length) {
+        return Err(format!("invalid packet length {length}"));
+    }
+
+    let mut body = vec![0u8; length as usize];
+    stream
+        .read_exact(&mut body)
+        .await
+        .map_err(|why| format!("could not read packet: {why}"))?;
+    let mut cursor = Cursor::new(body);
+    let id = read_varint(&mut cursor)?;
+    Ok((id, cursor))
+}
+
+async fn write_packet(stream: &mut TcpStream, id: i32, body: &[u8]) -> Result<(), String> {
+    let mut data: Vec<u8> = vec![];
+    write_varint(&mut data, id);
+    data.extend(body);
+    stream
+        .write_all(&packet(data))
+        .await
+        .map_err(|why| format!("could not send packet: {why}"))
+}
+
+/// Answers a minecraft client, see https://minecraft.wiki/w/Java_Edition_protocol.
+/// Returns whether it tried to join, server list pings don't wake the server.
+async fn minecraft(stream: &mut TcpStream, message: &str) -> Result<bool, String> {
+    let (_, mut handshake) = read_packet(stream).await?;
+    let protocol = read_varint(&mut handshake)?;
+    let _host = read_string(&mut handshake)?;
+    let mut port = [0u8; 2];
+    Read::read_exact(&mut handshake, &mut port)
+        .map_err(|why| format!("could not read packet: {why}"))?;
+    let next_state = read_varint(&mut handshake)?;
+
+    if next_state != 1 {
+        // login (or transfer): disconnect with the message, which the client shows
+        let mut body: Vec<u8> = vec![];
+        write_string(&mut body, &json!({ "text": message }).to_string());
+        write_packet(stream, 0x00, &body).await?;
+        return Ok(true);
+    }
+
+    // server list: status request, then a ping to be echoed
+    read_packet(stream).await?;
+    let status = json!({
+        // the protocol of the client, so it isn't shown as incompatible
+        "version": { "name": "sleeping", "protocol": protocol },
+        "players": { "max": 0, "online": 0 },
+        "description": { "text": "Sleeping, join to start it" },
+    });
+    let mut body: Vec<u8> = vec![];
+    write_string(&mut body, &status.to_string());
+    write_packet(stream, 0x00, &body).await?;
+
+    if let Ok((0x01, mut ping)) = read_packet(stream).await {
+        let mut payload = vec![];
+        Read::read_to_end(&mut ping, &mut payload).ok();
+        write_packet(stream, 0x01, &payload).await?;
+    }
+    Ok(false)
+}
+
+/// Listens until someone tries to connect, then releases the port and starts the server.
+async fn listen(
+    ctx: Context,
+    server: String,
+    name: String,
+    wake: WakeConfig,
+    listener: TcpListener,
+) {
+    let message = wake
+        .message
+        .clone()
+        .unwrap_or(format!("{name} is starting, try again in a minute."));
+
+    let address = loop {
+        let (mut stream, address) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(why) => {
+                // e.g. out of file descriptors, don't spin on it
+                println!("could not accept connection for {server}: {why}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let woken = match wake.protocol {
+            WakeProtocol::Tcp => Ok(true),
+            WakeProtocol::Minecraft => {
+                tokio::time::timeout(HANDSHAKE_TIMEOUT, minecraft(&mut stream, &message))
+                    .await
+                    .unwrap_or(Err("handshake timed out".to_string()))
+            }
+        };
+        match woken {
+            Ok(true) => break address,
+            Ok(false) => {}
+            Err(why) => println!("invalid connection to {server} from {address}: {why}"),
+        }
+    };
+    drop(listener);
+
+    // started from its own task, as starting releases this listener
+    tokio::spawn(async move {
+        // so the port isn't taken again while dependencies start
+        release(&ctx, &server).await;
+        let detail = format!("connection from {address}");
+        let answer = match gameserver::start(&ctx, &server, "wake", &detail).await {
+            Ok(answer) | Err(answer) => answer,
+        };
+        println!("woke {server}: {answer}");
+        notifications::send(
+            &ctx,
+            &format!("Someone tried to join **{name}**.\n{answer}"),
+        )
+        .await;
+    });
+}
+
+/// Holds the ports of stopped servers with `wake` in their config.json, starting them once someone
+/// tries to connect.
+pub fn spawn(ctx: Context) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        // only reported once, not every interval
+        let mut bind_failed: HashSet<String> = HashSet::new();
+
+        loop {
+            interval.tick().await;
+
+            let state = wake_state(&ctx).await;
+            for tracked in tracker::snapshot(&ctx).await {
+                let server = tracked.server.clone();
+                let wake = config::load(&server).wake;
+                let mut state = state.lock().await;
+
+                // forget listeners which woke their server
+                if state
+                    .listeners
+                    .get(&server)
+                    .is_some_and(|l| l.is_finished())
+                {
+                    state.listeners.remove(&server);
+                }
+
+                let recently_released = state.released.get(&server).is_some_and(|at| {
+                    at.elapsed() < Duration::from_secs(tracked.config.ready_timeout)
+                });
+                let should_listen = wake.is_some()
+                    && tracked.status.state == ServerState::Stopped
+                    && !recently_released;
+
+                match (should_listen, state.listeners.contains_key(&server)) {
+                    (false, true) => {
+                        if let Some(listener) = state.listeners.remove(&server) {
+                            listener.abort();
+                        }
+                    }
+                    (true, false) => {
+                        let wake = wake.expect("should_listen requires wake");
+                        match TcpListener::bind(&wake.address).await {
+                            Ok(listener) => {
+                                bind_failed.remove(&server);
+                                let task = listen(
+                                    ctx.clone(),
+                                    server.clone(),
+                                    tracked.config.name.clone(),
+                                    wake,
+                                    listener,
+                                );
+                                state.listeners.insert(server, tokio::spawn(task));
+                            }
+                            Err(why) => {
+                                if bind_failed.insert(server.clone()) {
+                                    println!(
+                                        "could not listen on {} for {server}: {why}",
+                                        wake.address
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+}